@@ -0,0 +1,47 @@
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use embytes_buffer::{Buffer, BufferWriter, CompactionPolicy};
+
+const PACKET: [u8; 16] = [0xAB; 16];
+
+/// Simulates a telemetry stream: write a small packet with a writer and read
+/// it back, leaving a few live bytes in the buffer.
+fn telemetry(buffer: &mut Buffer<[u8; 4096]>, packets: usize) {
+    for _ in 0..packets {
+        let mut writer = buffer.create_writer();
+        if writer.remaining_capacity() < PACKET.len() {
+            drop(writer);
+            buffer.shift();
+            writer = buffer.create_writer();
+        }
+        writer[..PACKET.len()].copy_from_slice(&PACKET);
+        writer.commit(PACKET.len()).unwrap();
+        drop(writer);
+
+        let n = buffer.remaining_len().saturating_sub(4);
+        buffer.skip(n).unwrap();
+    }
+}
+
+fn bench_policies(c: &mut Criterion) {
+    let mut group = c.benchmark_group("compaction");
+
+    let policies = [
+        ("always", CompactionPolicy::Always),
+        ("when_full", CompactionPolicy::WhenFull),
+        ("dead_50", CompactionPolicy::DeadCapacityExceeds(50)),
+        ("never", CompactionPolicy::Never),
+    ];
+
+    for (name, policy) in policies {
+        group.bench_with_input(BenchmarkId::from_parameter(name), &policy, |b, policy| {
+            let mut buffer = Buffer::<[u8; 4096]>::new_stack();
+            buffer.set_compaction_policy(*policy);
+            b.iter(|| telemetry(&mut buffer, 1024));
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_policies);
+criterion_main!(benches);
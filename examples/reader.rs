@@ -1,7 +1,6 @@
-use core::str::from_utf8;
 use std::io::Write;
 
-use embytes_buffer::{Buffer, BufferReader, ReadWrite};
+use embytes_buffer::{Buffer, BufferError, BufferReader, TextReader};
 
 
 
@@ -9,53 +8,59 @@ fn main () {
     let mut bytes = [0; 1024];
     let mut buffer = Buffer::new(&mut bytes);
 
-    // Write some bytes
-    buffer.write_all("👌abc".as_bytes()).unwrap();
+    // Write some bytes, the last character is only written partially
+    let text = "abc👌".as_bytes();
+    buffer.write_all(&text[..5]).unwrap();
 
     // try to read to a komma but there is none
-    let reader = buffer.create_reader();
-    let result = read_til_komma(&reader);
-    assert_eq!(result, None);
-    drop(reader);
+    assert_eq!(read_til_komma(&mut buffer), None);
 
-    // Write a string that now contains a comma
+    // Write the rest of the character and a string that now contains a comma
+    buffer.write_all(&text[5..]).unwrap();
     buffer.write_all("def,1234".as_bytes()).unwrap();
 
     // try to read to a komma. now there is one
-    let reader = buffer.create_reader();
-    let result = read_til_komma(&reader);
-    assert_eq!(result, Some("👌abcdef"));
-    drop(reader);
-
+    assert_eq!(read_til_komma(&mut buffer).as_deref(), Some("abc👌def"));
     assert_eq!(buffer.data(), "1234".as_bytes());
+
+    // Invalid UTF-8 is replaced instead of panicking
+    buffer.write_all(b"\xff5,").unwrap();
+    assert_eq!(read_til_komma(&mut buffer).as_deref(), Some("1234\u{FFFD}5"));
 }
 
 /// This method reads a string from buf until there is a comma
-/// 
+///
 /// Returns:
 /// - [`Option::None`] if the string is not complete yet
-/// - [`Option::Some`] with the string if there is a string
-fn read_til_komma<'a>(reader: &'a impl BufferReader) -> Option<&'a str> {
-
-    if reader.is_empty() {
-        return None;
-    }
-
-    let str = from_utf8(&reader)
-        .expect("expected valid utf8");
+/// - [`Option::Some`] with the string if there is a string. Invalid UTF-8 is replaced with [`char::REPLACEMENT_CHARACTER`]
+fn read_til_komma<T: AsMut<[u8]> + AsRef<[u8]>>(buffer: &mut Buffer<T>) -> Option<String> {
 
     // Find the position of the first comma
-    let comma_position = str.find(',');
-
-    if let Some(comma_position) = comma_position {
-        let data = &str[..comma_position];
-
-        let bytes_read = data.as_bytes().len() + 1;
+    let comma_position = buffer.find_byte(b',')?;
+
+    // Read the text before the comma
+    let reader = buffer.create_reader_with_max(comma_position);
+    let mut text = String::new();
+    loop {
+        match reader.read_str() {
+            Ok(valid) => text.push_str(valid),
+            Err(BufferError::InvalidUtf8) => {
+                text.push(char::REPLACEMENT_CHARACTER);
+                reader.add_bytes_read(1);
+            },
+            // All complete code points before the comma are read
+            Err(_) => break,
+        }
+    }
 
-        // Tell the reader that you have read `data.len() + 1` bytes
-        reader.add_bytes_read(bytes_read);
-        Some(data)
-    } else {
-        None
+    // A partial code point before the comma is invalid as well
+    let rest = comma_position - reader.bytes_read();
+    drop(reader);
+    if rest > 0 {
+        text.push(char::REPLACEMENT_CHARACTER);
     }
-}
\ No newline at end of file
+
+    // Consume the rest and the comma
+    buffer.skip(rest + 1).expect("the comma is buffered");
+    Some(text)
+}
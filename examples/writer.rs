@@ -1,4 +1,4 @@
-use embytes_buffer::{Buffer, BufferWriter, ReadWrite};
+use embytes_buffer::{Buffer, BufferWriter};
 use embedded_io::{Read, Write};
 
 
@@ -0,0 +1,50 @@
+
+/// Adapter that implements the [`embedded_io`] traits for a type implementing the [`std::io`] traits. 
+/// This allows to use std readers and writers with [`crate::BufferedReader`] and [`crate::BufferedWriter`].
+#[derive(Debug)]
+pub struct FromStd<T> {
+    inner: T,
+}
+
+impl <T> FromStd<T> {
+
+    /// Wraps a [`std::io`] reader or writer
+    pub fn new(inner: T) -> Self {
+        Self { inner }
+    }
+
+    /// Returns a reference to the wrapped reader or writer
+    pub fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped reader or writer
+    pub fn inner_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Returns the wrapped reader or writer
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl <T> embedded_io::ErrorType for FromStd<T> {
+    type Error = std::io::Error;
+}
+
+impl <T: std::io::Read> embedded_io::Read for FromStd<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        self.inner.read(buf)
+    }
+}
+
+impl <T: std::io::Write> embedded_io::Write for FromStd<T> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,210 @@
+use std::{io::{self, ErrorKind}, sync::{Arc, Mutex, MutexGuard}, task::{Context, Poll, Waker}};
+
+use crate::{Buffer, BufferCapacity, BufferIndex, BufferSource};
+
+struct State<T: BufferSource, I: BufferIndex = usize> {
+    buffer: Buffer<T, I>,
+    writers: usize,
+    closed: bool,
+    read_wakers: Vec<Waker>,
+    write_wakers: Vec<Waker>,
+}
+
+/// Adds `waker` to `wakers` if no waker of the same task is registered yet
+fn register(wakers: &mut Vec<Waker>, waker: &Waker) {
+    if ! wakers.iter().any(|w| w.will_wake(waker)) {
+        wakers.push(waker.clone());
+    }
+}
+
+/// Wakes and removes all registered wakers
+fn wake_all(wakers: &mut Vec<Waker>) {
+    for waker in wakers.drain(..) {
+        waker.wake();
+    }
+}
+
+/// A handle to a [`Buffer`] that is shared between async tasks. 
+/// 
+/// Instead of returning [`ErrorKind::WouldBlock`] the handle registers a waker and returns [`Poll::Pending`] 
+/// if the buffer is empty on read or full on write. Clone the handle to get another handle that can read and write, 
+/// use [`AsyncBuffer::reader`] to get a handle that can only read. 
+/// Any number of handles can wait on the same side, all of them are woken when the buffer changes.
+/// 
+/// After [`AsyncBuffer::close`] readers read the remaining data and then EOF, writers fail with [`ErrorKind::BrokenPipe`]. 
+/// The buffer is also closed when the last handle that can write is dropped.
+/// 
+/// For buffered reads each handle moves data into a local buffer with the capacity of the shared buffer.
+pub struct AsyncBuffer<T: BufferSource, I: BufferIndex = usize> {
+    shared: Arc<Mutex<State<T, I>>>,
+    local: Buffer<Vec<u8>>,
+    writer: bool,
+}
+
+impl <T: BufferSource, I: BufferIndex> Clone for AsyncBuffer<T, I> {
+    fn clone(&self) -> Self {
+        if self.writer {
+            self.lock().writers += 1;
+        }
+        Self {
+            shared: self.shared.clone(),
+            local: Buffer::new_heap(self.local.capacity()),
+            writer: self.writer,
+        }
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> AsyncBuffer<T, I> {
+
+    /// Creates a new [`AsyncBuffer`] from a [`Buffer`]
+    pub fn new(buffer: Buffer<T, I>) -> Self {
+        let capacity = buffer.capacity();
+        Self {
+            shared: Arc::new(Mutex::new(State {
+                buffer,
+                writers: 1,
+                closed: false,
+                read_wakers: Vec::new(),
+                write_wakers: Vec::new(),
+            })),
+            local: Buffer::new_heap(capacity),
+            writer: true,
+        }
+    }
+
+    /// Creates a new handle that can only read from the buffer. 
+    /// Writes with this handle fail with [`ErrorKind::PermissionDenied`].
+    pub fn reader(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            local: Buffer::new_heap(self.local.capacity()),
+            writer: false,
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T, I>> {
+        self.shared.lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Closes the buffer. Readers read the remaining data and then EOF.
+    pub fn close(&self) {
+        let mut state = self.lock();
+        state.closed = true;
+        wake_all(&mut state.read_wakers);
+        wake_all(&mut state.write_wakers);
+    }
+
+    /// Returns `true` if the buffer is closed
+    pub fn is_closed(&self) -> bool {
+        self.lock().closed
+    }
+
+    /// Returns the number of readable bytes in the shared buffer
+    pub fn len(&self) -> usize {
+        self.lock().buffer.remaining_len()
+    }
+
+    /// Returns `true` if there are no readable bytes in the shared buffer
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Reads from the shared buffer into `buf` or registers the waker if the buffer is empty
+    fn poll_read_shared(shared: &Mutex<State<T, I>>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let mut state = shared.lock()
+            .unwrap_or_else(|e| e.into_inner());
+
+        if state.buffer.has_remaining_len() {
+            let n = state.buffer.read_base(buf)
+                .map_err(|_| io::Error::from(ErrorKind::WouldBlock))?;
+            wake_all(&mut state.write_wakers);
+            Poll::Ready(Ok(n))
+        } else if state.closed {
+            Poll::Ready(Ok(0))
+        } else {
+            register(&mut state.read_wakers, cx.waker());
+            Poll::Pending
+        }
+    }
+
+    /// Reads data into `buf`. Returns [`Poll::Pending`] if the buffer is empty and not closed.
+    pub fn poll_read_into(&mut self, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        if self.local.has_remaining_len() {
+            return Poll::Ready(
+                self.local.read_base(buf)
+                    .map_err(|_| io::Error::from(ErrorKind::WouldBlock))
+            );
+        }
+
+        Self::poll_read_shared(&self.shared, cx, buf)
+    }
+
+    /// Writes data from `buf`. Returns [`Poll::Pending`] if the buffer is full.
+    pub fn poll_write_from(&mut self, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        if buf.is_empty() {
+            return Poll::Ready(Ok(0));
+        }
+
+        if ! self.writer {
+            return Poll::Ready(Err(ErrorKind::PermissionDenied.into()));
+        }
+
+        let mut state = self.lock();
+        if state.closed {
+            return Poll::Ready(Err(ErrorKind::BrokenPipe.into()));
+        }
+
+        if state.buffer.ensure_remaining_capacity() {
+            let n = state.buffer.write_base(buf)
+                .map_err(|_| io::Error::from(ErrorKind::WouldBlock))?;
+            wake_all(&mut state.read_wakers);
+            Poll::Ready(Ok(n))
+        } else {
+            register(&mut state.write_wakers, cx.waker());
+            Poll::Pending
+        }
+    }
+
+    /// Returns the locally buffered data and refills it from the shared buffer if it is empty
+    pub fn poll_fill_local(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        if ! self.local.has_remaining_len() {
+            self.local.reset();
+            let capacity = self.local.capacity();
+            let n = match Self::poll_read_shared(&self.shared, cx, &mut self.local.source[..capacity]) {
+                Poll::Ready(Ok(n)) => n,
+                Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                Poll::Pending => return Poll::Pending,
+            };
+            self.local.set_wpos(n);
+        }
+
+        Poll::Ready(Ok(self.local.data()))
+    }
+
+    /// Marks `amt` bytes returned by [`AsyncBuffer::poll_fill_local`] as read
+    pub fn consume_local(&mut self, amt: usize) {
+        self.local.skip(amt.min(self.local.remaining_len()))
+            .expect("AsyncBuffer: cannot consume more than the buffered data");
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> Drop for AsyncBuffer<T, I> {
+    fn drop(&mut self) {
+        if ! self.writer {
+            return;
+        }
+
+        let mut state = self.lock();
+        state.writers -= 1;
+        if state.writers == 0 {
+            state.closed = true;
+            wake_all(&mut state.read_wakers);
+            wake_all(&mut state.write_wakers);
+        }
+    }
+}
@@ -0,0 +1,514 @@
+//! Parser for responses of AT command modems.
+//!
+//! [`AtParser`] splits the data read from the modem into [`AtEvent`]s:
+//! complete responses ending with a final result code, unsolicited result codes (URCs),
+//! `>` prompts and binary payloads, also inside of a response.
+
+use crate::{BufferError, BufferReader};
+
+/// An event parsed by [`AtParser::next_event`]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum AtEvent<'a> {
+
+    /// An unsolicited result code outside of a response, e.g. `RING`
+    Urc(&'a [u8]),
+
+    /// A complete response to a command
+    Response(Response<'a>),
+
+    /// An information line of a pending response that is returned before the final result code, 
+    /// so the length of a following binary payload can be read, see [`AtParser::with_data_prefixes`]
+    Info(&'a [u8]),
+
+    /// The `>` prompt requesting the payload of a command like `AT+CMGS`
+    Prompt,
+
+    /// A binary payload requested with [`AtParser::expect_binary`]
+    Data(&'a [u8]),
+}
+
+/// The final result code that ends a [`Response`]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum FinalResult<'a> {
+
+    /// `OK`
+    Ok,
+
+    /// `ERROR`
+    Error,
+
+    /// `+CME ERROR: <err>` with the error code or text
+    CmeError(&'a [u8]),
+
+    /// `+CMS ERROR: <err>` with the error code or text
+    CmsError(&'a [u8]),
+
+    /// `NO CARRIER`
+    NoCarrier,
+
+    /// `BUSY`
+    Busy,
+
+    /// `NO ANSWER`
+    NoAnswer,
+
+    /// `NO DIALTONE`
+    NoDialtone,
+}
+
+/// A complete response to a command.
+/// The information lines can contain URCs the modem sent before the final result code,
+/// see [`Response::lines`] and [`Response::urcs`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Response<'a> {
+    raw: &'a [u8],
+    result: FinalResult<'a>,
+    urc_prefixes: &'a [&'a [u8]],
+}
+
+impl <'a> Response<'a> {
+
+    /// Returns the final result code of the response
+    pub fn result(&self) -> FinalResult<'a> {
+        self.result
+    }
+
+    /// Returns `true` if the final result code is `OK`
+    pub fn is_ok(&self) -> bool {
+        self.result == FinalResult::Ok
+    }
+
+    /// Returns the bytes of the information lines as read
+    pub fn raw(&self) -> &'a [u8] {
+        self.raw
+    }
+
+    /// Returns an iterator over the information lines without URCs
+    pub fn lines(&self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        let urc_prefixes = self.urc_prefixes;
+        split_lines(self.raw).filter(move |line| ! has_prefix(urc_prefixes, line))
+    }
+
+    /// Returns an iterator over the URCs sent before the final result code
+    pub fn urcs(&self) -> impl Iterator<Item = &'a [u8]> + 'a {
+        let urc_prefixes = self.urc_prefixes;
+        split_lines(self.raw).filter(move |line| has_prefix(urc_prefixes, line))
+    }
+}
+
+/// Parses [`AtEvent`]s from a [`BufferReader`].
+///
+/// Lines are terminated by `\r` or `\n`, empty lines are ignored.
+/// If no command is pending every line is a URC. After [`AtParser::expect_response`]
+/// the lines up to the next final result code form a [`Response`],
+/// except lines starting with one of the URC prefixes at the beginning of the response.
+/// The echo of the command is dropped. The line terminator after the final result code is consumed, 
+/// so a binary payload requested with [`AtParser::expect_binary`] can follow directly.
+///
+/// ```rust
+///     use embytes_buffer::{Buffer, ReadWrite, at::{AtEvent, AtParser}};
+///
+///     const URC_PREFIXES: &[&[u8]] = &[b"+CREG:", b"RING"];
+///
+///     let mut buf = Buffer::<[u8; 64]>::new_stack();
+///     let mut parser = AtParser::new(URC_PREFIXES);
+///
+///     parser.expect_response();
+///     buf.push(b"AT+CSQ\r\r\n+CREG: 5\r\n+CSQ: 20,99\r\n\r\nOK\r\n").unwrap();
+///
+///     let reader = buf.create_reader();
+///     assert_eq!(parser.next_event(&reader), Ok(AtEvent::Urc(b"+CREG: 5")));
+///     let Ok(AtEvent::Response(response)) = parser.next_event(&reader) else { panic!() };
+///     assert!(response.is_ok());
+///     assert!(response.lines().eq([&b"+CSQ: 20,99"[..]]));
+/// ```
+pub struct AtParser<'p> {
+    urc_prefixes: &'p [&'p [u8]],
+    data_prefixes: &'p [&'p [u8]],
+    pending: bool,
+    binary: Option<usize>,
+}
+
+impl <'p> AtParser<'p> {
+
+    /// Creates a parser that treats lines starting with one of `urc_prefixes` as URCs
+    pub const fn new(urc_prefixes: &'p [&'p [u8]]) -> Self {
+        Self {
+            urc_prefixes,
+            data_prefixes: &[],
+            pending: false,
+            binary: None,
+        }
+    }
+
+    /// Sets the prefixes of information lines that announce a binary payload inside of a response, e.g. `+QIRD:`.
+    /// 
+    /// If a pending response contains such a line, the lines up to and including it are returned 
+    /// one by one as [`AtEvent::Info`] and the lines after it are not scanned. 
+    /// Call [`AtParser::expect_binary`] with the announced length to read the payload, 
+    /// the lines after the payload form the [`Response`].
+    /// 
+    /// ```rust
+    ///     use embytes_buffer::{Buffer, ReadWrite, at::{AtEvent, AtParser}};
+    ///
+    ///     let mut buf = Buffer::<[u8; 64]>::new_stack();
+    ///     let mut parser = AtParser::new(&[]).with_data_prefixes(&[b"+QIRD:"]);
+    ///
+    ///     parser.expect_response();
+    ///     buf.push(b"AT+QIRD=0\r\r\n+QIRD: 4\r\n\r\nOK\r\n\r\nOK\r\n").unwrap();
+    ///
+    ///     let reader = buf.create_reader();
+    ///     assert_eq!(parser.next_event(&reader), Ok(AtEvent::Info(b"+QIRD: 4")));
+    ///     parser.expect_binary(4);
+    ///     assert_eq!(parser.next_event(&reader), Ok(AtEvent::Data(b"\r\nOK")));
+    ///     assert!(matches!(parser.next_event(&reader), Ok(AtEvent::Response(r)) if r.is_ok()));
+    /// ```
+    pub const fn with_data_prefixes(mut self, data_prefixes: &'p [&'p [u8]]) -> Self {
+        self.data_prefixes = data_prefixes;
+        self
+    }
+
+    /// Tells the parser that a command was sent and a response is expected
+    pub fn expect_response(&mut self) {
+        self.pending = true;
+    }
+
+    /// Returns `true` if a response is expected
+    pub fn is_pending(&self) -> bool {
+        self.pending
+    }
+
+    /// Tells the parser that the next `n` bytes are a binary payload returned as [`AtEvent::Data`]. 
+    /// Can be called after a [`AtEvent::Response`] or inside of a response after an [`AtEvent::Info`].
+    pub fn expect_binary(&mut self, n: usize) {
+        self.binary = Some(n);
+    }
+
+    /// Parses the next event from the unread bytes of `reader` and marks its bytes as read
+    ///
+    /// # Errors
+    ///
+    /// [`BufferError::NoData`] if there is no complete event, only leading line terminators and the echo are consumed
+    pub fn next_event<'a, R: BufferReader>(&mut self, reader: &'a R) -> Result<AtEvent<'a>, BufferError> where 'p: 'a {
+        if let Some(n) = self.binary {
+            let data = reader.unread().get(..n).ok_or(BufferError::NoData)?;
+            reader.add_bytes_read(n);
+            self.binary = None;
+            return Ok(AtEvent::Data(data));
+        }
+
+        loop {
+            let src = reader.unread();
+            let start = src.iter().position(|b| ! is_line_end(*b)).ok_or(BufferError::NoData)?;
+            reader.add_bytes_read(start);
+            let src = &src[start..];
+
+            if self.pending && src[0] == b'>' {
+                let n = if src.get(1) == Some(&b' ') { 2 } else { 1 };
+                reader.add_bytes_read(n);
+                return Ok(AtEvent::Prompt);
+            }
+
+            let line_len = src.iter().position(|b| is_line_end(*b)).ok_or(BufferError::NoData)?;
+            let line = &src[..line_len];
+
+            if ! self.pending || has_prefix(self.urc_prefixes, line) {
+                reader.add_bytes_read(line_len);
+                return Ok(AtEvent::Urc(line));
+            }
+
+            if is_echo(line) {
+                reader.add_bytes_read(line_len);
+                continue;
+            }
+
+            let mut offset = 0;
+            for line in split_terminated_lines(src) {
+                let line_end = line.as_ptr() as usize - src.as_ptr() as usize + line.len();
+
+                if has_prefix(self.data_prefixes, line) {
+                    // The first line is returned, the lines after the data line are not scanned
+                    let first_len = src.iter().position(|b| is_line_end(*b)).unwrap_or(src.len());
+                    let terminator = terminator_len(&src[first_len..]).ok_or(BufferError::NoData)?;
+                    reader.add_bytes_read(first_len + terminator);
+                    return Ok(AtEvent::Info(&src[..first_len]));
+                }
+
+                if let Some(result) = final_result(line) {
+                    let terminator = terminator_len(&src[line_end..]).ok_or(BufferError::NoData)?;
+                    reader.add_bytes_read(line_end + terminator);
+                    self.pending = false;
+                    return Ok(AtEvent::Response(Response {
+                        raw: &src[..offset],
+                        result,
+                        urc_prefixes: self.urc_prefixes,
+                    }));
+                }
+                offset = line_end;
+            }
+            return Err(BufferError::NoData);
+        }
+    }
+}
+
+fn is_line_end(b: u8) -> bool {
+    b == b'\r' || b == b'\n'
+}
+
+fn has_prefix(prefixes: &[&[u8]], line: &[u8]) -> bool {
+    prefixes.iter().any(|prefix| line.starts_with(prefix))
+}
+
+/// Returns the length of the line terminator at the start of `src`. 
+/// A single `\r` at the end of `src` can be followed by `\n`, so `None` is returned.
+fn terminator_len(src: &[u8]) -> Option<usize> {
+    match src {
+        [b'\r', b'\n', ..] => Some(2),
+        [b'\r'] | [] => None,
+        [b'\r' | b'\n', ..] => Some(1),
+        _ => Some(0),
+    }
+}
+
+fn is_echo(line: &[u8]) -> bool {
+    line.get(..2).is_some_and(|prefix| prefix.eq_ignore_ascii_case(b"AT"))
+}
+
+/// Returns the final result code if `line` is one
+fn final_result(line: &[u8]) -> Option<FinalResult<'_>> {
+    let result = match line {
+        b"OK" => FinalResult::Ok,
+        b"ERROR" => FinalResult::Error,
+        b"NO CARRIER" => FinalResult::NoCarrier,
+        b"BUSY" => FinalResult::Busy,
+        b"NO ANSWER" => FinalResult::NoAnswer,
+        b"NO DIALTONE" => FinalResult::NoDialtone,
+        _ => {
+            if let Some(err) = line.strip_prefix(b"+CME ERROR:") {
+                FinalResult::CmeError(err.trim_ascii_start())
+            } else if let Some(err) = line.strip_prefix(b"+CMS ERROR:") {
+                FinalResult::CmsError(err.trim_ascii_start())
+            } else {
+                return None;
+            }
+        }
+    };
+    Some(result)
+}
+
+/// Splits `src` into non-empty lines including a last unterminated line
+fn split_lines(src: &[u8]) -> impl Iterator<Item = &[u8]> {
+    src.split(|b| is_line_end(*b)).filter(|line| ! line.is_empty())
+}
+
+/// Splits `src` into non-empty terminated lines
+fn split_terminated_lines(src: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let end = src.iter().rposition(|b| is_line_end(*b)).unwrap_or(0);
+    split_lines(&src[..end])
+}
+
+#[cfg(feature = "async")]
+mod asynch {
+    use embedded_io_async::Read;
+
+    use crate::{Buffer, BufferError, BufferIndex, BufferedError, ReadWrite};
+    use super::{AtEvent, AtParser};
+
+    impl <'p> AtParser<'p> {
+
+        /// Reads from `src` into `buffer` until an event is complete and calls `f` with the event
+        ///
+        /// # Errors
+        ///
+        /// [`BufferedError::Io`] if `src` returned an error
+        /// [`BufferedError::UnexpectedEof`] if `src` reached EOF before the event was complete
+        /// [`BufferedError::Buffer`] with [`BufferError::NoCapacity`] if the event does not fit into the buffer
+        ///
+        /// # Cancel safety
+        ///
+        /// The read data is kept in `buffer`, so calling this method again after the future was dropped
+        /// continues where it stopped as long as the `read` implementation of `src` is cancel-safe.
+        pub async fn next_event_async<R, T, I, F, O>(&mut self, src: &mut R, buffer: &mut Buffer<T, I>, f: F) -> Result<O, BufferedError<R::Error>>
+        where
+            R: Read,
+            T: AsMut<[u8]> + AsRef<[u8]>,
+            I: BufferIndex,
+            F: FnOnce(AtEvent<'_>) -> O,
+        {
+            loop {
+                {
+                    let reader = buffer.create_reader();
+                    match self.next_event(&reader) {
+                        Ok(event) => return Ok(f(event)),
+                        Err(BufferError::NoData) => {},
+                        Err(e) => return Err(e.into()),
+                    }
+                }
+
+                if ! buffer.has_remaining_capacity() {
+                    buffer.shift();
+                }
+                let write_position = buffer.wpos();
+                let free = &mut buffer.source.as_mut()[write_position..];
+                if free.is_empty() {
+                    return Err(BufferError::NoCapacity.into());
+                }
+
+                let n = src.read(free).await.map_err(BufferedError::Io)?;
+                if n == 0 {
+                    return Err(BufferedError::UnexpectedEof);
+                }
+                buffer.set_wpos(write_position + n);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Buffer, BufferError, BufferReader, ReadWrite};
+    use super::{AtEvent, AtParser, FinalResult};
+
+    #[test]
+    fn test_urc_without_command() {
+        let mut buf = Buffer::<[u8; 32]>::new_stack();
+        let mut parser = AtParser::new(&[]);
+        buf.push(b"\r\nRING\r\n\r\nRI").unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(parser.next_event(&reader), Ok(AtEvent::Urc(b"RING")));
+        assert_eq!(parser.next_event(&reader), Err(BufferError::NoData));
+        assert_eq!(reader.unread(), b"RI");
+    }
+
+    #[test]
+    fn test_response_split_across_fills() {
+        let mut buf = Buffer::<[u8; 64]>::new_stack();
+        const URC_PREFIXES: &[&[u8]] = &[b"+CREG:"];
+        let mut parser = AtParser::new(URC_PREFIXES);
+        parser.expect_response();
+        buf.push(b"AT+COPS?\r\r\n+COPS: 0,0,\"NET\"\r\n+CR").unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(parser.next_event(&reader), Err(BufferError::NoData));
+        drop(reader);
+
+        buf.push(b"EG: 1\r\n\r\n+CME ERROR: 30\r\n").unwrap();
+        let reader = buf.create_reader();
+        let Ok(AtEvent::Response(response)) = parser.next_event(&reader) else { panic!("expected response") };
+        assert_eq!(response.result(), FinalResult::CmeError(b"30"));
+        assert!(response.lines().eq([&b"+COPS: 0,0,\"NET\""[..]]));
+        assert!(response.urcs().eq([&b"+CREG: 1"[..]]));
+        assert!(! parser.is_pending());
+        assert!(reader.unread().is_empty());
+    }
+
+    #[test]
+    fn test_prompt() {
+        let mut buf = Buffer::<[u8; 64]>::new_stack();
+        let mut parser = AtParser::new(&[]);
+        parser.expect_response();
+        buf.push(b"AT+CMGS=3\r\r\n> ").unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(parser.next_event(&reader), Ok(AtEvent::Prompt));
+        assert!(parser.is_pending());
+        drop(reader);
+
+        buf.push(b"\r\n+CMGS: 7\r\n\r\nOK\r\n").unwrap();
+        let reader = buf.create_reader();
+        let Ok(AtEvent::Response(response)) = parser.next_event(&reader) else { panic!("expected response") };
+        assert!(response.is_ok());
+        assert!(response.lines().eq([&b"+CMGS: 7"[..]]));
+    }
+
+    #[test]
+    fn test_binary_after_response() {
+        let mut buf = Buffer::<[u8; 64]>::new_stack();
+        let mut parser = AtParser::new(&[]);
+        parser.expect_response();
+        buf.push(b"\r\nOK\r").unwrap();
+
+        // The terminator of the final result code is not complete yet
+        let reader = buf.create_reader();
+        assert_eq!(parser.next_event(&reader), Err(BufferError::NoData));
+        drop(reader);
+
+        buf.push(b"\n\ra\nb").unwrap();
+        let reader = buf.create_reader();
+        let Ok(AtEvent::Response(response)) = parser.next_event(&reader) else { panic!("expected response") };
+        assert!(response.is_ok());
+
+        parser.expect_binary(4);
+        assert_eq!(parser.next_event(&reader), Ok(AtEvent::Data(b"\ra\nb")));
+        assert!(reader.unread().is_empty());
+    }
+
+    #[test]
+    fn test_binary_inside_response() {
+        const DATA_PREFIXES: &[&[u8]] = &[b"+QIRD:"];
+        let mut buf = Buffer::<[u8; 64]>::new_stack();
+        let mut parser = AtParser::new(&[]).with_data_prefixes(DATA_PREFIXES);
+        parser.expect_response();
+        buf.push(b"AT+QIRD=0\r\r\n+QISTATE: 1\r\n+QIRD: 4\r\nOK\r\n").unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(parser.next_event(&reader), Ok(AtEvent::Info(b"+QISTATE: 1")));
+        assert_eq!(parser.next_event(&reader), Ok(AtEvent::Info(b"+QIRD: 4")));
+        assert!(parser.is_pending());
+
+        // The payload looks like the end of the response
+        parser.expect_binary(4);
+        assert_eq!(parser.next_event(&reader), Ok(AtEvent::Data(b"OK\r\n")));
+        assert_eq!(parser.next_event(&reader), Err(BufferError::NoData));
+        drop(reader);
+
+        buf.push(b"\r\n\r\nOK\r\n").unwrap();
+        let reader = buf.create_reader();
+        let Ok(AtEvent::Response(response)) = parser.next_event(&reader) else { panic!("expected response") };
+        assert!(response.is_ok());
+        assert_eq!(response.lines().count(), 0);
+        assert!(reader.unread().is_empty());
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn test_next_event_async() {
+        use embassy_futures::block_on;
+        use embedded_io_async::{ErrorKind, ErrorType, Read};
+
+        use crate::BufferedError;
+
+        /// Returns one chunk per read
+        struct Chunks<'a>(&'a [&'a [u8]]);
+
+        impl ErrorType for Chunks<'_> {
+            type Error = ErrorKind;
+        }
+
+        impl Read for Chunks<'_> {
+            async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+                let Some((chunk, rest)) = self.0.split_first() else { return Ok(0) };
+                assert!(chunk.len() <= buf.len());
+                buf[..chunk.len()].copy_from_slice(chunk);
+                self.0 = rest;
+                Ok(chunk.len())
+            }
+        }
+
+        let mut src = Chunks(&[b"AT\r\r", b"\nO", b"K\r\n", b"RI"]);
+        let mut buf = Buffer::<[u8; 6]>::new_stack();
+        let mut parser = AtParser::new(&[]);
+        parser.expect_response();
+
+        block_on(async {
+            let ok = parser.next_event_async(&mut src, &mut buf, |event| {
+                matches!(event, AtEvent::Response(r) if r.is_ok())
+            }).await;
+            assert_eq!(ok, Ok(true));
+
+            let eof = parser.next_event_async(&mut src, &mut buf, |_| ()).await;
+            assert_eq!(eof, Err(BufferedError::UnexpectedEof));
+        });
+    }
+}
@@ -0,0 +1,288 @@
+use embedded_io::Read;
+
+use crate::{Buffer, BufferCapacity, BufferError, BufferIndex, BufferReader, BufferSource, BufferWriter, BufferedError, ReadWrite};
+
+/// A reader that refills a [`Buffer`] on demand from an [`embedded_io::Read`] source. 
+/// The buffered data can be parsed with a [`BufferReader`] created by [`BufferedReader::create_reader`].
+/// 
+/// # Example
+/// 
+/// ```rust
+///     use embytes_buffer::{Buffer, BufferedReader, BufferReader};
+/// 
+///     let uart: &[u8] = b"AT\r\nOK\r\n";
+///     let mut reader = BufferedReader::new(uart, Buffer::<[u8; 64]>::new_stack());
+/// 
+///     let line = reader.fill_until_delim(b'\n').unwrap();
+///     assert_eq!(line, b"AT\r\n");
+///     let n = line.len();
+/// 
+///     let r = reader.create_reader();
+///     r.add_bytes_read(n);
+/// ```
+pub struct BufferedReader<R, T: BufferSource, I: BufferIndex = usize> {
+    inner: R,
+    buffer: Buffer<T, I>,
+}
+
+#[cfg(feature = "std")]
+/// A [`BufferedReader`] that reads from a [`std::io::Read`] source
+pub type StdBufferedReader<R, T, I = usize> = BufferedReader<crate::FromStd<R>, T, I>;
+
+#[cfg(feature = "std")]
+impl <R: std::io::Read, T: BufferSource, I: BufferIndex> BufferedReader<crate::FromStd<R>, T, I> {
+
+    /// Creates a new [`BufferedReader`] that reads from a [`std::io::Read`] source
+    pub fn from_std(inner: R, buffer: Buffer<T, I>) -> Self {
+        Self::new(crate::FromStd::new(inner), buffer)
+    }
+}
+
+impl <R, T: BufferSource, I: BufferIndex> BufferedReader<R, T, I> {
+
+    /// Creates a new [`BufferedReader`] that reads from `inner` into `buffer`
+    pub fn new(inner: R, buffer: Buffer<T, I>) -> Self {
+        Self { inner, buffer }
+    }
+
+    /// Returns a reference to the underlying source
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying source
+    pub fn inner_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns a reference to the buffer
+    pub fn buffer(&self) -> &Buffer<T, I> {
+        &self.buffer
+    }
+
+    /// Returns a mutable reference to the buffer
+    pub fn buffer_mut(&mut self) -> &mut Buffer<T, I> {
+        &mut self.buffer
+    }
+
+    /// Returns the underlying source and the buffer
+    pub fn into_parts(self) -> (R, Buffer<T, I>) {
+        (self.inner, self.buffer)
+    }
+
+    /// Creates a reader to read the buffered data
+    pub fn create_reader<'a>(&'a mut self) -> impl BufferReader + 'a {
+        self.buffer.create_reader()
+    }
+
+    /// Marks `n` buffered bytes as read
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoData`] if less than `n` bytes are buffered
+    pub fn consume(&mut self, n: usize) -> Result<(), BufferError> {
+        self.buffer.skip(n)
+    }
+}
+
+impl <R: Read, T: BufferSource, I: BufferIndex> BufferedReader<R, T, I> {
+
+    /// Reads once from the source into the free space of the buffer. 
+    /// Dead capacity is only reclaimed if the [`crate::CompactionPolicy`] of the buffer allows it. 
+    /// Returns the number of bytes read, `0` means the source reached EOF.
+    fn read_once(&mut self) -> Result<usize, BufferedError<R::Error>> {
+        if ! self.buffer.ensure_remaining_capacity() {
+            return Err(BufferError::NoCapacity.into());
+        }
+
+        let mut writer = self.buffer.create_writer();
+
+        let n = self.inner.read(&mut writer)
+            .map_err(BufferedError::Io)?;
+        writer.commit(n)?;
+        Ok(n)
+    }
+
+    /// Returns the buffered data. If the buffer is empty it is refilled from the source once. 
+    /// An empty slice is returned if the source reached EOF.
+    pub fn fill_buf(&mut self) -> Result<&[u8], BufferedError<R::Error>> {
+        if ! self.buffer.has_remaining_len() {
+            self.read_once()?;
+        }
+
+        Ok(self.buffer.data())
+    }
+
+    /// Refills the buffer until at least `n` bytes are buffered and returns the first `n` bytes
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferedError::Buffer`] with [`BufferError::NoCapacity`] if `n` is greater than the capacity of the buffer
+    /// [`BufferedError::UnexpectedEof`] if the source reached EOF before `n` bytes were buffered
+    pub fn fill_until(&mut self, n: usize) -> Result<&[u8], BufferedError<R::Error>> {
+        if n > self.buffer.capacity() {
+            return Err(BufferError::NoCapacity.into());
+        }
+
+        while self.buffer.remaining_len() < n {
+            if self.read_once()? == 0 {
+                return Err(BufferedError::UnexpectedEof);
+            }
+        }
+
+        Ok(&self.buffer.data()[..n])
+    }
+
+    /// Refills the buffer until it contains `delim` and returns the data up to and including `delim`
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferedError::Buffer`] with [`BufferError::NoCapacity`] if the buffer is full and does not contain `delim`
+    /// [`BufferedError::UnexpectedEof`] if the source reached EOF before `delim` was read
+    pub fn fill_until_delim(&mut self, delim: u8) -> Result<&[u8], BufferedError<R::Error>> {
+        let mut searched = 0;
+        loop {
+            let data = self.buffer.data();
+            if let Some(pos) = data[searched..].iter().position(|b| *b == delim) {
+                return Ok(&self.buffer.data()[..searched + pos + 1]);
+            }
+            searched = data.len();
+
+            if self.read_once()? == 0 {
+                return Err(BufferedError::UnexpectedEof);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_io::{ErrorKind, ErrorType, Read};
+
+    use crate::{Buffer, BufferError, BufferReader, BufferedError, CompactionPolicy, SmallBuffer, UninitArray};
+
+    use super::BufferedReader;
+
+    /// Returns the provided chunks one per read
+    struct ChunkReader<'a> {
+        chunks: &'a [&'a [u8]],
+    }
+
+    impl <'a> ErrorType for ChunkReader<'a> {
+        type Error = ErrorKind;
+    }
+
+    impl <'a> Read for ChunkReader<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            match self.chunks.split_first() {
+                Some((chunk, rest)) => {
+                    assert!(buf.len() >= chunk.len());
+                    buf[..chunk.len()].copy_from_slice(chunk);
+                    self.chunks = rest;
+                    Ok(chunk.len())
+                },
+                None => Ok(0),
+            }
+        }
+    }
+
+    #[test]
+    fn test_fill_until() {
+        let source = ChunkReader { chunks: &[&[1, 2], &[3], &[4, 5]] };
+        let mut reader = BufferedReader::new(source, Buffer::<[u8; 8]>::new_stack());
+
+        assert_eq!(reader.fill_until(4).unwrap(), &[1, 2, 3, 4]);
+        assert_eq!(reader.buffer().data(), &[1, 2, 3, 4, 5]);
+
+        reader.consume(5).unwrap();
+        assert_eq!(reader.fill_until(1), Err(BufferedError::UnexpectedEof));
+    }
+
+    #[test]
+    fn test_fill_until_no_capacity() {
+        let source = ChunkReader { chunks: &[] };
+        let mut reader = BufferedReader::new(source, Buffer::<[u8; 4]>::new_stack());
+
+        assert_eq!(reader.fill_until(5), Err(BufferedError::Buffer(BufferError::NoCapacity)));
+    }
+
+    #[test]
+    fn test_fill_until_delim() {
+        let source = ChunkReader { chunks: &[b"ab", b"c,d", b"e,"] };
+        let mut reader = BufferedReader::new(source, Buffer::<[u8; 8]>::new_stack());
+
+        assert_eq!(reader.fill_until_delim(b',').unwrap(), b"abc,");
+        let reader_ref = reader.create_reader();
+        reader_ref.add_bytes_read(4);
+        drop(reader_ref);
+
+        assert_eq!(reader.fill_until_delim(b',').unwrap(), b"de,");
+    }
+
+    #[test]
+    fn test_fill_until_delim_shifts() {
+        let source = ChunkReader { chunks: &[b"abc,", b"defg", b","] };
+        let mut reader = BufferedReader::new(source, Buffer::<[u8; 6]>::new_stack());
+
+        assert_eq!(reader.fill_until_delim(b',').unwrap(), b"abc,");
+        reader.consume(4).unwrap();
+
+        assert_eq!(reader.fill_until_delim(b',').unwrap(), b"defg,");
+    }
+
+    #[test]
+    fn test_fill_until_delim_full() {
+        let source = ChunkReader { chunks: &[b"abcd"] };
+        let mut reader = BufferedReader::new(source, Buffer::<[u8; 4]>::new_stack());
+
+        assert_eq!(reader.fill_until_delim(b','), Err(BufferedError::Buffer(BufferError::NoCapacity)));
+    }
+
+    #[test]
+    fn test_fill_until_delim_never_compacts() {
+        let source = ChunkReader { chunks: &[b"abc,", b"de", b","] };
+        let mut buffer = Buffer::<[u8; 6]>::new_stack();
+        buffer.set_compaction_policy(CompactionPolicy::Never);
+        let mut reader = BufferedReader::new(source, buffer);
+
+        assert_eq!(reader.fill_until_delim(b',').unwrap(), b"abc,");
+        reader.consume(4).unwrap();
+
+        // The dead capacity of the consumed bytes is not reclaimed
+        assert_eq!(reader.fill_until_delim(b','), Err(BufferedError::Buffer(BufferError::NoCapacity)));
+        assert_eq!(reader.buffer().data(), b"de");
+    }
+
+    #[test]
+    fn test_small_and_uninit_buffer() {
+        let source = ChunkReader { chunks: &[b"ab,", b"c,"] };
+        let mut reader = BufferedReader::new(source, SmallBuffer::<8, u8>::new_stack());
+        assert_eq!(reader.fill_until_delim(b',').unwrap(), b"ab,");
+
+        let source = ChunkReader { chunks: &[&[1, 2], &[3]] };
+        let mut reader = BufferedReader::new(source, Buffer::<UninitArray<8>>::new_uninit());
+        assert_eq!(reader.fill_until(3).unwrap(), &[1, 2, 3]);
+    }
+
+    #[test]
+    fn test_fill_buf() {
+        let source = ChunkReader { chunks: &[&[1, 2], &[3]] };
+        let mut reader = BufferedReader::new(source, Buffer::<[u8; 8]>::new_stack());
+
+        assert_eq!(reader.fill_buf().unwrap(), &[1, 2]);
+        assert_eq!(reader.fill_buf().unwrap(), &[1, 2]);
+        reader.consume(2).unwrap();
+        assert_eq!(reader.fill_buf().unwrap(), &[3]);
+        reader.consume(1).unwrap();
+        assert_eq!(reader.fill_buf().unwrap(), &[] as &[u8]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_std_reader() {
+        let source = std::io::Cursor::new(b"hello\nworld".to_vec());
+        let mut reader = BufferedReader::from_std(source, Buffer::new_heap(16));
+
+        assert_eq!(reader.fill_until_delim(b'\n').unwrap(), b"hello\n");
+    }
+}
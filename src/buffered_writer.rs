@@ -0,0 +1,351 @@
+use embedded_io::{ErrorType, Write};
+
+use crate::{Buffer, BufferCapacity, BufferIndex, BufferSource, BufferedError};
+
+/// A writer that collects small writes in a [`Buffer`] and flushes them to an [`embedded_io::Write`] sink. 
+/// 
+/// The buffered data is written to the sink if the number of buffered bytes reaches the flush threshold, 
+/// if [`embedded_io::Write::flush`] is called or if a write does not fit into the buffer. 
+/// Writes that are larger than the buffer bypass the buffer.
+/// 
+/// A write that was buffered returns `Ok` even if flushing at the threshold failed. 
+/// The data stays in the buffer and the error is returned by the next write or flush.
+/// 
+/// # Example
+/// 
+/// ```rust
+///     use embedded_io::Write;
+///     use embytes_buffer::{Buffer, BufferedWriter};
+/// 
+///     let mut radio = [0u8; 64];
+///     let mut writer = BufferedWriter::new(&mut radio[..], Buffer::<[u8; 16]>::new_stack());
+/// 
+///     writer.write_all(b"tiny").unwrap();
+///     writer.write_all(b"packets").unwrap();
+///     writer.flush().unwrap();
+/// 
+///     let (_, buffer) = writer.into_parts();
+///     assert_eq!(buffer.remaining_len(), 0);
+///     assert_eq!(&radio[..11], b"tinypackets");
+/// ```
+pub struct BufferedWriter<W: ErrorType, T: BufferSource, I: BufferIndex = usize> {
+    inner: W,
+    buffer: Buffer<T, I>,
+    threshold: Option<usize>,
+    error: Option<BufferedError<W::Error>>,
+}
+
+#[cfg(feature = "std")]
+/// A [`BufferedWriter`] that writes to a [`std::io::Write`] sink
+pub type StdBufferedWriter<W, T, I = usize> = BufferedWriter<crate::FromStd<W>, T, I>;
+
+#[cfg(feature = "std")]
+impl <W: std::io::Write, T: BufferSource, I: BufferIndex> BufferedWriter<crate::FromStd<W>, T, I> {
+
+    /// Creates a new [`BufferedWriter`] that writes to a [`std::io::Write`] sink
+    pub fn from_std(inner: W, buffer: Buffer<T, I>) -> Self {
+        Self::new(crate::FromStd::new(inner), buffer)
+    }
+}
+
+impl <W: ErrorType, T: BufferSource, I: BufferIndex> BufferedWriter<W, T, I> {
+
+    /// Creates a new [`BufferedWriter`] that writes to `inner` and flushes if the buffer is full
+    pub fn new(inner: W, buffer: Buffer<T, I>) -> Self {
+        Self { inner, buffer, threshold: None, error: None }
+    }
+
+    /// Sets the number of buffered bytes at which the buffer is flushed to the sink. 
+    /// `None` flushes only if the buffer is full.
+    pub fn set_flush_threshold(&mut self, threshold: Option<usize>) {
+        self.threshold = threshold;
+    }
+
+    /// Returns the flush threshold, see [`BufferedWriter::set_flush_threshold`]
+    pub fn flush_threshold(&self) -> Option<usize> {
+        self.threshold
+    }
+
+    /// Returns a reference to the underlying sink
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying sink. 
+    /// Writing to the sink directly bypasses the buffered data.
+    pub fn inner_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Returns a reference to the buffer
+    pub fn buffer(&self) -> &Buffer<T, I> {
+        &self.buffer
+    }
+
+    /// Returns the underlying sink and the buffer. The buffered data is not flushed.
+    pub fn into_parts(self) -> (W, Buffer<T, I>) {
+        (self.inner, self.buffer)
+    }
+
+    /// Returns the number of buffered bytes at which the buffer is flushed
+    fn effective_threshold(&self) -> usize {
+        let capacity = self.buffer.capacity();
+        self.threshold.map_or(capacity, |t| t.min(capacity))
+    }
+}
+
+impl <W: Write, T: BufferSource, I: BufferIndex> BufferedWriter<W, T, I> {
+
+    /// Writes all buffered data to the sink without flushing the sink. 
+    /// Partial writes of the sink are handled by advancing the read position of the buffer.
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferedError::Io`] if the sink returns an error. The data that was not written stays in the buffer.
+    /// [`BufferedError::WriteZero`] if the sink accepted no bytes
+    pub fn flush_buf(&mut self) -> Result<(), BufferedError<W::Error>> {
+        while self.buffer.has_remaining_len() {
+            let n = self.inner.write(self.buffer.data())
+                .map_err(BufferedError::Io)?;
+
+            if n == 0 {
+                return Err(BufferedError::WriteZero);
+            }
+            self.buffer.skip(n)?;
+        }
+
+        self.buffer.reset();
+        Ok(())
+    }
+}
+
+impl <W: ErrorType, T: BufferSource, I: BufferIndex> ErrorType for BufferedWriter<W, T, I> {
+    type Error = BufferedError<W::Error>;
+}
+
+impl <W: Write, T: BufferSource, I: BufferIndex> Write for BufferedWriter<W, T, I> {
+
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.buffer.capacity() - self.buffer.remaining_len() < buf.len() {
+            self.flush_buf()?;
+        }
+
+        if buf.len() >= self.buffer.capacity() {
+            return self.inner.write(buf)
+                .map_err(BufferedError::Io);
+        }
+
+        self.buffer.ensure_remaining_capacity();
+        let n = self.buffer.write_base(buf)?;
+
+        if self.buffer.remaining_len() >= self.effective_threshold() {
+            // The bytes are buffered: the error is returned by the next write or flush, 
+            // returning it now would make the caller write the bytes again
+            self.error = self.flush_buf().err();
+        }
+
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        if let Some(e) = self.error.take() {
+            return Err(e);
+        }
+
+        self.flush_buf()?;
+        self.inner.flush()
+            .map_err(BufferedError::Io)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_io::{ErrorKind, ErrorType, Write};
+
+    use crate::{Buffer, BufferedError, SmallBuffer, UninitArray};
+
+    use super::BufferedWriter;
+
+    /// Accepts at most `max_write` bytes per write and records each write
+    struct ChunkSink {
+        data: [u8; 64],
+        len: usize,
+        writes: usize,
+        max_write: usize,
+    }
+
+    impl ChunkSink {
+        fn new(max_write: usize) -> Self {
+            Self { data: [0; 64], len: 0, writes: 0, max_write }
+        }
+
+        fn written(&self) -> &[u8] {
+            &self.data[..self.len]
+        }
+    }
+
+    impl ErrorType for ChunkSink {
+        type Error = ErrorKind;
+    }
+
+    impl Write for ChunkSink {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            let n = buf.len().min(self.max_write);
+            self.data[self.len..self.len + n].copy_from_slice(&buf[..n]);
+            self.len += n;
+            self.writes += 1;
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_coalesce_until_full() {
+        let mut writer = BufferedWriter::new(ChunkSink::new(64), Buffer::<[u8; 8]>::new_stack());
+
+        writer.write_all(&[1, 2, 3]).unwrap();
+        writer.write_all(&[4, 5, 6]).unwrap();
+        assert_eq!(writer.inner().writes, 0);
+
+        writer.write_all(&[7, 8, 9]).unwrap();
+        assert_eq!(writer.inner().writes, 1);
+        assert_eq!(writer.inner().written(), &[1, 2, 3, 4, 5, 6]);
+
+        writer.flush().unwrap();
+        assert_eq!(writer.inner().written(), &[1, 2, 3, 4, 5, 6, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_flush_threshold() {
+        let mut writer = BufferedWriter::new(ChunkSink::new(64), Buffer::<[u8; 8]>::new_stack());
+        writer.set_flush_threshold(Some(4));
+
+        writer.write_all(&[1, 2, 3]).unwrap();
+        assert_eq!(writer.inner().writes, 0);
+
+        writer.write_all(&[4]).unwrap();
+        assert_eq!(writer.inner().writes, 1);
+        assert_eq!(writer.buffer().remaining_len(), 0);
+    }
+
+    #[test]
+    fn test_partial_writes() {
+        let mut writer = BufferedWriter::new(ChunkSink::new(3), Buffer::<[u8; 8]>::new_stack());
+
+        writer.write_all(&[1, 2, 3, 4, 5, 6, 7]).unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(writer.inner().writes, 3);
+        assert_eq!(writer.inner().written(), &[1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn test_large_write_bypasses_buffer() {
+        let mut writer = BufferedWriter::new(ChunkSink::new(64), Buffer::<[u8; 4]>::new_stack());
+
+        writer.write_all(&[1]).unwrap();
+        writer.write_all(&[2, 3, 4, 5, 6]).unwrap();
+
+        assert_eq!(writer.inner().writes, 2);
+        assert_eq!(writer.inner().written(), &[1, 2, 3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_write_zero() {
+        let mut writer = BufferedWriter::new(ChunkSink::new(0), Buffer::<[u8; 4]>::new_stack());
+
+        writer.write_all(&[1, 2]).unwrap();
+        assert_eq!(writer.flush(), Err(BufferedError::WriteZero));
+        assert_eq!(writer.buffer().data(), &[1, 2]);
+    }
+
+    /// Fails the first write and accepts all following writes
+    struct FailOnceSink {
+        inner: ChunkSink,
+        failed: bool,
+    }
+
+    impl ErrorType for FailOnceSink {
+        type Error = ErrorKind;
+    }
+
+    impl Write for FailOnceSink {
+        fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            if !self.failed {
+                self.failed = true;
+                return Err(ErrorKind::Other);
+            }
+            self.inner.write(buf)
+        }
+
+        fn flush(&mut self) -> Result<(), Self::Error> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_failed_threshold_flush_keeps_data() {
+        let sink = FailOnceSink { inner: ChunkSink::new(64), failed: false };
+        let mut writer = BufferedWriter::new(sink, Buffer::<[u8; 8]>::new_stack());
+        writer.set_flush_threshold(Some(2));
+
+        writer.write_all(b"ab").unwrap();
+        assert_eq!(writer.buffer().data(), b"ab");
+
+        // The error of the threshold flush is returned once
+        assert_eq!(writer.flush(), Err(BufferedError::Io(ErrorKind::Other)));
+        assert_eq!(writer.buffer().data(), b"ab");
+
+        writer.flush().unwrap();
+        assert_eq!(writer.inner().inner.written(), b"ab");
+    }
+
+    #[test]
+    fn test_failed_threshold_flush_returned_by_write() {
+        let sink = FailOnceSink { inner: ChunkSink::new(64), failed: false };
+        let mut writer = BufferedWriter::new(sink, Buffer::<[u8; 8]>::new_stack());
+        writer.set_flush_threshold(Some(2));
+
+        writer.write_all(b"ab").unwrap();
+        assert_eq!(writer.write(b"c"), Err(BufferedError::Io(ErrorKind::Other)));
+
+        writer.write_all(b"c").unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.inner().inner.written(), b"abc");
+    }
+
+    #[test]
+    fn test_small_and_uninit_buffer() {
+        let mut writer = BufferedWriter::new(ChunkSink::new(64), SmallBuffer::<4, u8>::new_stack());
+        writer.write_all(&[1, 2, 3]).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.inner().written(), &[1, 2, 3]);
+
+        let mut writer = BufferedWriter::new(ChunkSink::new(64), Buffer::<UninitArray<4>>::new_uninit());
+        writer.write_all(&[4, 5, 6, 7, 8]).unwrap();
+        writer.flush().unwrap();
+        assert_eq!(writer.inner().written(), &[4, 5, 6, 7, 8]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_std_writer() {
+        let mut writer = BufferedWriter::from_std(Vec::new(), Buffer::new_heap(16));
+
+        writer.write_all(b"hello").unwrap();
+        writer.flush().unwrap();
+
+        assert_eq!(writer.inner().inner(), b"hello");
+    }
+}
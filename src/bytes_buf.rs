@@ -0,0 +1,118 @@
+use bytes::{buf::UninitSlice, Buf, BufMut};
+
+use crate::{Buffer, BufferCapacity, BufferIndex, CompactionPolicy};
+
+impl <T: AsMut<[u8]> + AsRef<[u8]>, I: BufferIndex> Buf for Buffer<T, I> {
+    fn remaining(&self) -> usize {
+        self.wpos() - self.rpos()
+    }
+
+    fn chunk(&self) -> &[u8] {
+        self.data()
+    }
+
+    fn advance(&mut self, cnt: usize) {
+        self.skip(cnt)
+            .expect("Buffer: cannot advance past the readable data");
+    }
+}
+
+/// The free region is returned by [`BufMut::chunk_mut`]. 
+/// If the free region is empty, dead capacity is reclaimed with [`Buffer::shift`] 
+/// unless the [`CompactionPolicy`] is [`CompactionPolicy::Never`].
+unsafe impl <T: AsMut<[u8]> + AsRef<[u8]>, I: BufferIndex> BufMut for Buffer<T, I> {
+    fn remaining_mut(&self) -> usize {
+        if self.compaction == CompactionPolicy::Never {
+            self.remaining_capacity()
+        } else {
+            self.capacity() - (self.wpos() - self.rpos())
+        }
+    }
+
+    unsafe fn advance_mut(&mut self, cnt: usize) {
+        assert!(cnt <= self.remaining_capacity(), "Buffer: cannot commit more bytes than the remaining capacity");
+        self.set_wpos(self.wpos() + cnt);
+    }
+
+    fn chunk_mut(&mut self) -> &mut UninitSlice {
+        if self.may_shift_for(1) {
+            self.shift();
+        }
+
+        let write_position = self.wpos();
+        UninitSlice::new(&mut self.source.as_mut()[write_position..])
+    }
+}
+
+#[cfg(feature = "std")]
+impl Buffer<Vec<u8>> {
+
+    /// Converts the readable data into [`bytes::Bytes`] without copying
+    pub fn into_bytes(self) -> bytes::Bytes {
+        let (read_position, write_position) = (self.read_position, self.write_position);
+        bytes::Bytes::from(self.source).slice(read_position..write_position)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<Buffer<Vec<u8>>> for bytes::Bytes {
+    fn from(value: Buffer<Vec<u8>>) -> Self {
+        value.into_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::{Buf, BufMut};
+
+    use crate::{Buffer, CompactionPolicy};
+
+    #[test]
+    fn test_buf() {
+        let mut buf = Buffer::<[u8; 8]>::new_stack();
+        buf.push(&[1, 2, 3, 4]).unwrap();
+
+        assert_eq!(buf.remaining(), 4);
+        assert_eq!(buf.get_u16(), 0x0102);
+        assert_eq!(buf.chunk(), &[3, 4]);
+        assert_eq!(buf.read_position, 2);
+    }
+
+    #[test]
+    fn test_buf_mut() {
+        let mut buf = Buffer::<[u8; 4]>::new_stack();
+
+        buf.put_u16(0x0102);
+        buf.put_slice(&[3, 4]);
+        assert_eq!(buf.data(), &[1, 2, 3, 4]);
+        assert_eq!(buf.remaining_mut(), 0);
+
+        buf.advance(2);
+        assert_eq!(buf.remaining_mut(), 2);
+        buf.put_u8(5);
+        assert_eq!(buf.data(), &[3, 4, 5]);
+    }
+
+    #[test]
+    fn test_buf_mut_never_compact() {
+        let mut buf = Buffer::<[u8; 4]>::new_stack();
+        buf.set_compaction_policy(CompactionPolicy::Never);
+
+        buf.put_slice(&[1, 2, 3, 4]);
+        buf.advance(2);
+        assert_eq!(buf.remaining_mut(), 0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_into_bytes() {
+        let mut buf = Buffer::new_heap(8);
+        buf.push(&[1, 2, 3, 4]).unwrap();
+        buf.skip(1).unwrap();
+        let ptr = buf.data().as_ptr();
+
+        let bytes = buf.into_bytes();
+        assert_eq!(&bytes[..], &[2, 3, 4]);
+        assert_eq!(bytes.as_ptr(), ptr);
+    }
+}
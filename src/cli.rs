@@ -0,0 +1,553 @@
+//! Line editor for command shells on a serial terminal.
+//!
+//! [`LineEditor`] is fed with the bytes received from a VT100 compatible terminal.
+//! It keeps the current line in a [`Buffer`], writes the echo to a [`BufferWriter`]
+//! and stores the entered lines in a history [`Buffer`].
+//!
+//! ```rust
+//!     use embytes_buffer::{Buffer, ReadWrite, cli::LineEditor};
+//!
+//!     let mut editor = LineEditor::new(Buffer::<[u8; 64]>::new_stack(), Buffer::<[u8; 128]>::new_stack());
+//!     let mut echo = Buffer::<[u8; 128]>::new_stack();
+//!     let mut writer = echo.create_writer();
+//!
+//!     let mut complete = false;
+//!     for b in b"led  on\r" {
+//!         complete = editor.feed(*b, &mut writer).unwrap();
+//!     }
+//!     assert!(complete);
+//!
+//!     let mut argv = [&[][..]; 4];
+//!     let argc = editor.argv(&mut argv).unwrap();
+//!     assert_eq!(&argv[..argc], &[&b"led"[..], &b"on"[..]]);
+//! ```
+
+use crate::{Buffer, BufferCapacity, BufferError, BufferWriter};
+
+const BACKSPACE: u8 = 0x08;
+const BELL: u8 = 0x07;
+const ESC: u8 = 0x1b;
+const DEL: u8 = 0x7f;
+
+/// State of a VT100 escape sequence
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Escape {
+    None,
+    Esc,
+    /// `ESC [` with the numeric parameter
+    Csi(u8),
+    /// `ESC [` with the first numeric parameter followed by more parameters or intermediate bytes
+    CsiParams(u8),
+    /// `ESC O`
+    Ss3,
+}
+
+/// An editing key decoded from the input
+#[derive(Debug, PartialEq, Clone, Copy)]
+enum Key {
+    Char(u8),
+    Backspace,
+    Delete,
+    Left,
+    Right,
+    Home,
+    End,
+    Up,
+    Down,
+    Enter,
+}
+
+/// A part of the echo
+enum Out<'a> {
+    Bytes(&'a [u8]),
+    Repeat(u8, usize),
+}
+
+impl <'a> Out<'a> {
+    fn len(&self) -> usize {
+        match self {
+            Out::Bytes(bytes) => bytes.len(),
+            Out::Repeat(_, n) => *n,
+        }
+    }
+}
+
+/// A line editor that supports backspace, delete, cursor movement and a history.
+///
+/// The line is stored in `line`, the history in `history` with every entry terminated by `\n`.
+/// If the history is full the oldest entries are discarded.
+///
+/// While the history is browsed, the line that was edited before is kept in the free space of `history`
+/// and restored by moving down past the newest entry. If the free space is too small, the oldest entries are discarded.
+pub struct LineEditor<L: AsMut<[u8]> + AsRef<[u8]>, H: AsMut<[u8]> + AsRef<[u8]>> {
+    line: Buffer<L>,
+    history: Buffer<H>,
+    cursor: usize,
+    history_index: Option<usize>,
+    draft_len: usize,
+    escape: Escape,
+    last_cr: bool,
+    complete: bool,
+}
+
+impl <L: AsMut<[u8]> + AsRef<[u8]>, H: AsMut<[u8]> + AsRef<[u8]>> LineEditor<L, H> {
+
+    /// Creates a line editor. The readable data of `line` is the initial line
+    pub fn new(mut line: Buffer<L>, history: Buffer<H>) -> Self {
+        line.shift();
+        let cursor = line.wpos();
+        Self {
+            line,
+            history,
+            cursor,
+            history_index: None,
+            draft_len: 0,
+            escape: Escape::None,
+            last_cr: false,
+            complete: false,
+        }
+    }
+
+    /// Returns the current line. After [`LineEditor::feed`] returned `true` this is the entered line
+    pub fn line(&self) -> &[u8] {
+        self.line.data()
+    }
+
+    /// Returns the position of the cursor in the line
+    pub fn cursor(&self) -> usize {
+        self.cursor
+    }
+
+    /// Returns an iterator over the history starting with the newest entry
+    pub fn history(&self) -> impl Iterator<Item = &[u8]> {
+        history_entries(self.history.data())
+    }
+
+    /// Splits the current line into arguments, see [`tokenize`]
+    ///
+    /// # Errors
+    ///
+    /// [`BufferError::NoCapacity`] if the line has more than `argv.len()` arguments
+    pub fn argv<'a>(&'a self, argv: &mut [&'a [u8]]) -> Result<usize, BufferError> {
+        tokenize(self.line(), argv)
+    }
+
+    /// Processes the input byte `b` and writes the echo to `echo`.
+    /// Returns `true` if a line was entered, it is available with [`LineEditor::line`] until the next key is fed.
+    ///
+    /// If the line is full, printable characters are dropped and a bell is echoed.
+    ///
+    /// # Errors
+    ///
+    /// [`BufferError::NoCapacity`] if the echo does not fit into `echo`, the byte is not processed
+    pub fn feed<W: BufferWriter>(&mut self, b: u8, echo: &mut W) -> Result<bool, BufferError> {
+        let (escape, key) = self.decode(b);
+        let key = match key {
+            // The `\n` of a `\r\n`
+            Some(Key::Enter) if b == b'\n' && self.last_cr => None,
+            key => key,
+        };
+
+        if let Some(key) = key {
+            let (len, cursor) = (self.line.wpos(), self.cursor);
+            let complete = core::mem::replace(&mut self.complete, false);
+            if complete {
+                self.line.reset();
+                self.cursor = 0;
+            }
+
+            if let Err(e) = self.apply(key, echo) {
+                // The entered line is kept until a key is processed
+                self.line.set_wpos(len);
+                self.cursor = cursor;
+                self.complete = complete;
+                return Err(e);
+            }
+        }
+
+        self.escape = escape;
+        self.last_cr = b == b'\r';
+        Ok(self.complete)
+    }
+
+    /// Decodes `b` and returns the next escape state and the key if it is complete
+    fn decode(&self, b: u8) -> (Escape, Option<Key>) {
+        match (self.escape, b) {
+            (Escape::None, ESC) => (Escape::Esc, None),
+            (Escape::None, b'\r' | b'\n') => (Escape::None, Some(Key::Enter)),
+            (Escape::None, BACKSPACE | DEL) => (Escape::None, Some(Key::Backspace)),
+            (Escape::None, b' '..=b'~') => (Escape::None, Some(Key::Char(b))),
+            (Escape::None, _) => (Escape::None, None),
+            (Escape::Esc, b'[') => (Escape::Csi(0), None),
+            (Escape::Esc, b'O') => (Escape::Ss3, None),
+            (Escape::Csi(n), b'0'..=b'9') => (Escape::Csi(n.saturating_mul(10).saturating_add(b - b'0')), None),
+            // Further parameter bytes like the modifier of `ESC [1;5C` and intermediate bytes are ignored
+            (Escape::Csi(n) | Escape::CsiParams(n), b'0'..=b'?' | b' '..=b'/') => (Escape::CsiParams(n), None),
+            (Escape::Csi(n) | Escape::CsiParams(n), b'~') => (Escape::None, match n {
+                1 | 7 => Some(Key::Home),
+                3 => Some(Key::Delete),
+                4 | 8 => Some(Key::End),
+                _ => None,
+            }),
+            (Escape::Csi(_) | Escape::CsiParams(_) | Escape::Ss3, b'A') => (Escape::None, Some(Key::Up)),
+            (Escape::Csi(_) | Escape::CsiParams(_) | Escape::Ss3, b'B') => (Escape::None, Some(Key::Down)),
+            (Escape::Csi(_) | Escape::CsiParams(_) | Escape::Ss3, b'C') => (Escape::None, Some(Key::Right)),
+            (Escape::Csi(_) | Escape::CsiParams(_) | Escape::Ss3, b'D') => (Escape::None, Some(Key::Left)),
+            (Escape::Csi(_) | Escape::CsiParams(_) | Escape::Ss3, b'H') => (Escape::None, Some(Key::Home)),
+            (Escape::Csi(_) | Escape::CsiParams(_) | Escape::Ss3, b'F') => (Escape::None, Some(Key::End)),
+            // Any other final byte ends an unsupported sequence
+            _ => (Escape::None, None),
+        }
+    }
+
+    /// Applies `key` to the line if the echo fits into `echo`
+    fn apply<W: BufferWriter>(&mut self, key: Key, echo: &mut W) -> Result<(), BufferError> {
+        let len = self.line.wpos();
+        let cursor = self.cursor;
+        let tail = &self.line.data()[cursor..];
+
+        match key {
+            Key::Char(_) if ! self.line.has_remaining_capacity() => {
+                write_echo(echo, &[Out::Bytes(&[BELL])])?;
+            },
+            Key::Char(c) => {
+                write_echo(echo, &[Out::Bytes(&[c]), Out::Bytes(tail), Out::Repeat(BACKSPACE, tail.len())])?;
+                let src = self.line.source.as_mut();
+                src.copy_within(cursor..len, cursor + 1);
+                src[cursor] = c;
+                self.line.set_wpos(len + 1);
+                self.cursor += 1;
+            },
+            Key::Backspace if cursor > 0 => {
+                write_echo(echo, &[Out::Bytes(&[BACKSPACE]), Out::Bytes(tail), Out::Bytes(b" "), Out::Repeat(BACKSPACE, tail.len() + 1)])?;
+                self.remove(cursor - 1);
+                self.cursor -= 1;
+            },
+            Key::Delete if cursor < len => {
+                let tail = &tail[1..];
+                write_echo(echo, &[Out::Bytes(tail), Out::Bytes(b" "), Out::Repeat(BACKSPACE, tail.len() + 1)])?;
+                self.remove(cursor);
+            },
+            Key::Left if cursor > 0 => {
+                write_echo(echo, &[Out::Bytes(&[BACKSPACE])])?;
+                self.cursor -= 1;
+            },
+            Key::Right if cursor < len => {
+                write_echo(echo, &[Out::Bytes(&tail[..1])])?;
+                self.cursor += 1;
+            },
+            Key::Home => {
+                write_echo(echo, &[Out::Repeat(BACKSPACE, cursor)])?;
+                self.cursor = 0;
+            },
+            Key::End => {
+                write_echo(echo, &[Out::Bytes(tail)])?;
+                self.cursor = len;
+            },
+            Key::Up => {
+                let index = self.history_index.map_or(0, |i| i + 1);
+                if index < self.history().count() {
+                    self.recall(Some(index), echo)?;
+                }
+            },
+            Key::Down => {
+                match self.history_index {
+                    Some(0) => self.recall(None, echo)?,
+                    Some(i) => self.recall(Some(i - 1), echo)?,
+                    None => {},
+                }
+            },
+            Key::Enter => {
+                write_echo(echo, &[Out::Bytes(b"\r\n")])?;
+                self.push_history();
+                self.history_index = None;
+                self.draft_len = 0;
+                self.cursor = len;
+                self.complete = true;
+            },
+            _ => {},
+        }
+        Ok(())
+    }
+
+    /// Removes the byte at `index` from the line
+    fn remove(&mut self, index: usize) {
+        let len = self.line.wpos();
+        self.line.source.as_mut().copy_within(index + 1..len, index);
+        self.line.set_wpos(len - 1);
+    }
+
+    /// Returns the history entry `index` or the line that was edited before the history was browsed
+    fn entry(&self, index: Option<usize>) -> &[u8] {
+        let entry = match index {
+            Some(i) => history_entries(self.history.data()).nth(i).unwrap_or_default(),
+            None => {
+                let start = self.history.wpos();
+                &self.history.source.as_ref()[start..start + self.draft_len]
+            },
+        };
+        &entry[..entry.len().min(self.line.capacity())]
+    }
+
+    /// Replaces the line with the history entry `index` or the line that was edited before
+    fn recall<W: BufferWriter>(&mut self, index: Option<usize>, echo: &mut W) -> Result<(), BufferError> {
+        let entry = self.entry(index);
+        let len = self.line.wpos();
+        let clear = len.saturating_sub(entry.len());
+        write_echo(echo, &[
+            Out::Repeat(BACKSPACE, self.cursor),
+            Out::Bytes(entry),
+            Out::Repeat(b' ', clear),
+            Out::Repeat(BACKSPACE, clear),
+        ])?;
+
+        if let (None, Some(i)) = (self.history_index, index) {
+            self.save_draft(i + 1);
+        }
+
+        let entry = match index {
+            Some(i) => history_entries(self.history.data()).nth(i).unwrap_or_default(),
+            None => {
+                let start = self.history.wpos();
+                &self.history.source.as_ref()[start..start + self.draft_len]
+            },
+        };
+        let n = entry.len().min(self.line.capacity());
+        self.line.source.as_mut()[..n].copy_from_slice(&entry[..n]);
+        self.line.set_wpos(n);
+        self.cursor = n;
+        self.history_index = index;
+        Ok(())
+    }
+
+    /// Keeps the line in the free space of the history while the history is browsed.
+    /// Discards the oldest entries but keeps the newest `keep` entries if the free space is too small.
+    fn save_draft(&mut self, keep: usize) {
+        let len = self.line.wpos();
+        self.history.shift();
+        while self.history.remaining_capacity() < len && history_entries(self.history.data()).count() > keep {
+            let oldest = self.history.data().iter().position(|b| *b == b'\n').map_or(0, |n| n + 1);
+            self.history.skip(oldest).expect("entry is readable");
+            self.history.shift();
+        }
+
+        if self.history.remaining_capacity() < len {
+            self.draft_len = 0;
+            return;
+        }
+
+        let start = self.history.wpos();
+        self.history.source.as_mut()[start..start + len].copy_from_slice(self.line.data());
+        self.draft_len = len;
+    }
+
+    /// Appends the line to the history unless it is empty or equal to the newest entry
+    fn push_history(&mut self) {
+        let line = self.line.data();
+        if line.is_empty() || line.len() + 1 > self.history.capacity() || self.history().next() == Some(line) {
+            return;
+        }
+
+        while self.history.capacity() - (self.history.wpos() - self.history.rpos()) < line.len() + 1 {
+            let oldest = self.history.data().iter().position(|b| *b == b'\n').map_or(0, |n| n + 1);
+            self.history.skip(oldest).expect("entry is readable");
+        }
+
+        self.history.push_all(&[line, b"\n"]).expect("capacity checked");
+    }
+}
+
+/// Returns an iterator over the entries of the history `data` starting with the newest entry
+fn history_entries(data: &[u8]) -> impl Iterator<Item = &[u8]> {
+    let data = data.strip_suffix(b"\n").unwrap_or(data);
+    data.rsplit(|b| *b == b'\n').filter(move |_| ! data.is_empty())
+}
+
+/// Writes all `parts` to `writer` and commits them as a whole
+fn write_echo<W: BufferWriter>(writer: &mut W, parts: &[Out<'_>]) -> Result<(), BufferError> {
+    let len: usize = parts.iter().map(Out::len).sum();
+    if writer.remaining_capacity() < len {
+        return Err(BufferError::NoCapacity);
+    }
+
+    let mut offset = 0;
+    for part in parts {
+        match part {
+            Out::Bytes(bytes) => writer[offset..offset + bytes.len()].copy_from_slice(bytes),
+            Out::Repeat(b, n) => writer[offset..offset + n].fill(*b),
+        }
+        offset += part.len();
+    }
+    writer.commit(len)
+}
+
+/// Splits `line` at spaces and tabs into `argv` and returns the number of arguments.
+/// Arguments in double quotes may contain spaces, the quotes are not part of the argument.
+///
+/// # Errors
+///
+/// [`BufferError::NoCapacity`] if the line has more than `argv.len()` arguments
+pub fn tokenize<'a>(line: &'a [u8], argv: &mut [&'a [u8]]) -> Result<usize, BufferError> {
+    let mut argc = 0;
+    let mut rest = line;
+    loop {
+        let start = match rest.iter().position(|b| *b != b' ' && *b != b'\t') {
+            Some(start) => start,
+            None => return Ok(argc),
+        };
+        rest = &rest[start..];
+
+        let (arg, end) = if rest[0] == b'"' {
+            let close = rest[1..].iter().position(|b| *b == b'"').map_or(rest.len(), |n| n + 1);
+            (&rest[1..close], close + 1)
+        } else {
+            let end = rest.iter().position(|b| *b == b' ' || *b == b'\t').unwrap_or(rest.len());
+            (&rest[..end], end)
+        };
+
+        *argv.get_mut(argc).ok_or(BufferError::NoCapacity)? = arg;
+        argc += 1;
+        rest = rest.get(end..).unwrap_or_default();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Buffer, BufferError};
+    use super::{tokenize, LineEditor};
+
+    fn feed_all(editor: &mut LineEditor<[u8; 16], [u8; 16]>, input: &[u8], echo: &mut Buffer<[u8; 128]>) -> bool {
+        let mut writer = echo.create_writer();
+        let mut complete = false;
+        for b in input {
+            complete = editor.feed(*b, &mut writer).unwrap();
+        }
+        complete
+    }
+
+    fn editor() -> LineEditor<[u8; 16], [u8; 16]> {
+        LineEditor::new(Buffer::new_stack(), Buffer::new_stack())
+    }
+
+    #[test]
+    fn test_insert_and_backspace() {
+        let mut editor = editor();
+        let mut echo = Buffer::new_stack();
+
+        assert!(! feed_all(&mut editor, b"abd\x08c", &mut echo));
+        assert_eq!(editor.line(), b"abc");
+        assert_eq!(echo.data(), b"abd\x08 \x08c");
+    }
+
+    #[test]
+    fn test_cursor_movement() {
+        let mut editor = editor();
+        let mut echo = Buffer::new_stack();
+
+        feed_all(&mut editor, b"ac\x1b[Db", &mut echo);
+        assert_eq!(editor.line(), b"abc");
+        assert_eq!(editor.cursor(), 2);
+        assert_eq!(echo.data(), b"ac\x08bc\x08");
+
+        echo.reset();
+        feed_all(&mut editor, b"\x1b[H\x1b[3~\x1b[F", &mut echo);
+        assert_eq!(editor.line(), b"bc");
+        assert_eq!(editor.cursor(), 2);
+        assert_eq!(echo.data(), b"\x08\x08bc \x08\x08\x08bc");
+    }
+
+    #[test]
+    fn test_enter_crlf_and_history() {
+        let mut editor = editor();
+        let mut echo = Buffer::new_stack();
+
+        assert!(feed_all(&mut editor, b"one\r\n", &mut echo));
+        assert_eq!(editor.line(), b"one");
+        assert!(feed_all(&mut editor, b"two\r", &mut echo));
+        assert!(feed_all(&mut editor, b"\n\r", &mut echo));
+        assert_eq!(editor.line(), b"");
+        assert!(editor.history().eq([&b"two"[..], &b"one"[..]]));
+
+        echo.reset();
+        feed_all(&mut editor, b"x\x1b[A\x1b[A", &mut echo);
+        assert_eq!(editor.line(), b"one");
+        assert_eq!(echo.data(), b"x\x08two\x08\x08\x08one");
+
+        // Moving down past the newest entry restores the edited line
+        feed_all(&mut editor, b"\x1b[B\x1b[B", &mut echo);
+        assert_eq!(editor.line(), b"x");
+        assert_eq!(editor.cursor(), 1);
+    }
+
+    #[test]
+    fn test_restore_line_discards_oldest() {
+        let mut editor = editor();
+        let mut echo = Buffer::new_stack();
+
+        feed_all(&mut editor, b"first\rsecond\rdraft\x1b[A", &mut echo);
+        assert_eq!(editor.line(), b"second");
+        assert!(editor.history().eq([&b"second"[..]]));
+
+        feed_all(&mut editor, b"\x1b[B", &mut echo);
+        assert_eq!(editor.line(), b"draft");
+    }
+
+    #[test]
+    fn test_ignore_csi_parameters() {
+        let mut editor = editor();
+        let mut echo = Buffer::new_stack();
+
+        // Ctrl+Right and Ctrl+Left are handled like Right and Left
+        feed_all(&mut editor, b"ab\x1b[1;5C", &mut echo);
+        assert_eq!(editor.line(), b"ab");
+        feed_all(&mut editor, b"\x1b[1;5Dc", &mut echo);
+        assert_eq!(editor.line(), b"acb");
+
+        // Unsupported sequences are dropped completely
+        feed_all(&mut editor, b"\x1b[2J\x1b[?25l", &mut echo);
+        assert_eq!(editor.line(), b"acb");
+    }
+
+    #[test]
+    fn test_history_discards_oldest() {
+        let mut editor = editor();
+        let mut echo = Buffer::new_stack();
+
+        feed_all(&mut editor, b"first\rsecond\rthird\r", &mut echo);
+        assert!(editor.history().eq([&b"third"[..], &b"second"[..]]));
+    }
+
+    #[test]
+    fn test_echo_no_capacity() {
+        let mut editor = editor();
+        let mut echo = Buffer::<[u8; 1]>::new_stack();
+        let mut writer = echo.create_writer();
+
+        editor.feed(b'a', &mut writer).unwrap();
+        assert_eq!(editor.feed(b'b', &mut writer), Err(BufferError::NoCapacity));
+        assert_eq!(editor.line(), b"a");
+    }
+
+    #[test]
+    fn test_echo_no_capacity_keeps_entered_line() {
+        let mut editor = editor();
+        let mut echo = Buffer::new_stack();
+        assert!(feed_all(&mut editor, b"one\r", &mut echo));
+
+        let mut full = Buffer::<[u8; 0]>::new_stack();
+        assert_eq!(editor.feed(b'x', &mut full.create_writer()), Err(BufferError::NoCapacity));
+        assert_eq!(editor.line(), b"one");
+
+        feed_all(&mut editor, b"x", &mut echo);
+        assert_eq!(editor.line(), b"x");
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let mut argv = [&[][..]; 3];
+        assert_eq!(tokenize(b"  set \"a b\"\tc ", &mut argv), Ok(3));
+        assert_eq!(argv, [&b"set"[..], &b"a b"[..], &b"c"[..]]);
+        assert_eq!(tokenize(b"a b c d", &mut argv), Err(BufferError::NoCapacity));
+        assert_eq!(tokenize(b"", &mut argv), Ok(0));
+    }
+}
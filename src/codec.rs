@@ -0,0 +1,182 @@
+use bytes::{Buf, BytesMut};
+use tokio_util::codec::{Decoder, Encoder};
+
+use crate::{Buffer, BufferError, BufferReader, BufferWriter, ReadWrite};
+
+/// A parser that decodes items from a [`BufferReader`]. 
+/// Used with [`BufferCodec`] to decode frames with [`tokio_util::codec`].
+pub trait BufferDecoder {
+    /// The decoded item
+    type Item;
+
+    /// The error returned if decoding fails
+    type Error: From<std::io::Error>;
+
+    /// Decodes an item from `reader` and marks the used bytes with [`BufferReader::add_bytes_read`]. 
+    /// Returns `None` if more data is needed.
+    fn decode(&mut self, reader: &impl BufferReader) -> Result<Option<Self::Item>, Self::Error>;
+}
+
+/// An encoder that writes items to a [`BufferWriter`]. 
+/// Used with [`BufferCodec`] to encode frames with [`tokio_util::codec`].
+pub trait BufferEncoder<Item> {
+    /// The error returned if encoding fails
+    type Error: From<std::io::Error> + From<BufferError>;
+
+    /// Encodes `item` into `writer` and commits the written bytes with [`BufferWriter::commit`]
+    fn encode(&mut self, item: Item, writer: &mut impl BufferWriter) -> Result<(), Self::Error>;
+}
+
+/// Bridge between [`BufferDecoder`] / [`BufferEncoder`] and [`Decoder`] / [`Encoder`] 
+/// 
+/// Encoded frames must not be larger than `max_frame_len`.
+pub struct BufferCodec<C> {
+    inner: C,
+    max_frame_len: usize,
+}
+
+impl <C> BufferCodec<C> {
+
+    /// Creates a new [`BufferCodec`] that encodes frames with at most `max_frame_len` bytes
+    pub fn new(inner: C, max_frame_len: usize) -> Self {
+        Self { inner, max_frame_len }
+    }
+
+    /// Returns a reference to the wrapped decoder or encoder
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the wrapped decoder or encoder
+    pub fn inner_mut(&mut self) -> &mut C {
+        &mut self.inner
+    }
+}
+
+impl <C: BufferDecoder> Decoder for BufferCodec<C> {
+    type Item = C::Item;
+    type Error = C::Error;
+
+    fn decode(&mut self, src: &mut BytesMut) -> Result<Option<Self::Item>, Self::Error> {
+        let len = src.len();
+        let mut buffer = Buffer::new(&mut src[..]);
+        buffer.write_position = len;
+
+        let reader = buffer.create_reader();
+        let item = self.inner.decode(&reader)?;
+        drop(reader);
+
+        let n = buffer.read_position;
+        src.advance(n);
+        Ok(item)
+    }
+}
+
+impl <Item, C: BufferEncoder<Item>> Encoder<Item> for BufferCodec<C> {
+    type Error = C::Error;
+
+    fn encode(&mut self, item: Item, dst: &mut BytesMut) -> Result<(), Self::Error> {
+        let start = dst.len();
+        dst.resize(start + self.max_frame_len, 0);
+
+        let mut buffer = Buffer::new(&mut dst[start..]);
+        let mut writer = buffer.create_writer();
+        let result = self.inner.encode(item, &mut writer);
+        drop(writer);
+
+        let n = buffer.remaining_len();
+        dst.truncate(start + n);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::BytesMut;
+    use tokio_util::codec::{Decoder, Encoder};
+
+    use crate::{BufferError, BufferReader, BufferWriter};
+
+    use super::{BufferCodec, BufferDecoder, BufferEncoder};
+
+    /// Frames are a length byte followed by the payload
+    struct LengthPrefixed;
+
+    #[derive(Debug)]
+    enum CodecError {
+        Io,
+        Buffer(BufferError),
+    }
+
+    impl From<std::io::Error> for CodecError {
+        fn from(_: std::io::Error) -> Self {
+            CodecError::Io
+        }
+    }
+
+    impl From<BufferError> for CodecError {
+        fn from(e: BufferError) -> Self {
+            CodecError::Buffer(e)
+        }
+    }
+
+    impl BufferDecoder for LengthPrefixed {
+        type Item = Vec<u8>;
+        type Error = CodecError;
+
+        fn decode(&mut self, reader: &impl BufferReader) -> Result<Option<Self::Item>, Self::Error> {
+            let Some(len) = reader.first() else {
+                return Ok(None);
+            };
+            let len = *len as usize;
+            if reader.len() < len + 1 {
+                return Ok(None);
+            }
+
+            reader.add_bytes_read(len + 1);
+            Ok(Some(reader[1..len + 1].to_vec()))
+        }
+    }
+
+    impl BufferEncoder<&[u8]> for LengthPrefixed {
+        type Error = CodecError;
+
+        fn encode(&mut self, item: &[u8], writer: &mut impl BufferWriter) -> Result<(), Self::Error> {
+            if writer.remaining_capacity() < item.len() + 1 {
+                return Err(BufferError::NoCapacity.into());
+            }
+            writer[0] = item.len() as u8;
+            writer[1..item.len() + 1].copy_from_slice(item);
+            writer.commit(item.len() + 1)?;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_decode_partial_frames() {
+        let mut codec = BufferCodec::new(LengthPrefixed, 16);
+        let mut src = BytesMut::from(&[3, 1, 2][..]);
+
+        assert_eq!(codec.decode(&mut src).unwrap(), None);
+        assert_eq!(src.len(), 3);
+
+        src.extend_from_slice(&[3, 1, 9]);
+        assert_eq!(codec.decode(&mut src).unwrap(), Some(vec![1, 2, 3]));
+        assert_eq!(codec.decode(&mut src).unwrap(), Some(vec![9]));
+        assert!(src.is_empty());
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut codec = BufferCodec::new(LengthPrefixed, 16);
+        let mut dst = BytesMut::new();
+
+        codec.encode(&[1, 2][..], &mut dst).unwrap();
+        codec.encode(&[3][..], &mut dst).unwrap();
+        assert_eq!(&dst[..], &[2, 1, 2, 1, 3]);
+
+        let result = codec.encode(&[0; 16][..], &mut dst);
+        assert!(matches!(result, Err(CodecError::Buffer(BufferError::NoCapacity))));
+        assert_eq!(&dst[..], &[2, 1, 2, 1, 3]);
+    }
+}
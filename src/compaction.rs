@@ -0,0 +1,85 @@
+
+/// Policy that decides when a [`crate::Buffer`] reclaims dead capacity by shifting 
+/// the readable data to the start of the source.
+/// 
+/// The policy is applied when a writer is created with [`crate::ReadWrite::create_writer`]. 
+/// Writing with [`crate::Buffer::push`] or the io trait implementations compacts on demand 
+/// if the data would not fit otherwise, unless the policy is [`CompactionPolicy::Never`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CompactionPolicy {
+    /// Compact every time a writer is created
+    #[default]
+    Always,
+
+    /// Compact only if there is no remaining capacity
+    WhenFull,
+
+    /// Compact if the dead capacity exceeds the given percentage of the capacity
+    DeadCapacityExceeds(u8),
+
+    /// Never compact automatically. Use [`crate::Buffer::shift`] to compact manually.
+    Never,
+}
+
+impl CompactionPolicy {
+
+    /// Returns `true` if a buffer with the given state should be compacted
+    pub fn should_compact(&self, capacity: usize, read_position: usize, write_position: usize) -> bool {
+        if read_position == 0 {
+            return false;
+        }
+
+        match self {
+            CompactionPolicy::Always => true,
+            CompactionPolicy::WhenFull => write_position >= capacity,
+            CompactionPolicy::DeadCapacityExceeds(percent) => {
+                read_position * 100 > capacity * (*percent as usize)
+            },
+            CompactionPolicy::Never => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Buffer;
+    use super::CompactionPolicy;
+
+    #[test]
+    fn test_no_dead_capacity() {
+        assert!(! CompactionPolicy::Always.should_compact(8, 0, 4));
+    }
+
+    #[test]
+    fn test_when_full() {
+        assert!(! CompactionPolicy::WhenFull.should_compact(8, 2, 6));
+        assert!(CompactionPolicy::WhenFull.should_compact(8, 2, 8));
+    }
+
+    #[test]
+    fn test_dead_capacity_exceeds() {
+        let policy = CompactionPolicy::DeadCapacityExceeds(50);
+        assert!(! policy.should_compact(8, 4, 8));
+        assert!(policy.should_compact(8, 5, 8));
+    }
+
+    #[test]
+    fn test_never() {
+        assert!(! CompactionPolicy::Never.should_compact(8, 8, 8));
+    }
+
+    #[test]
+    fn test_never_ensure_remaining_capacity() {
+        let mut buf = Buffer::<[u8; 4]>::new_stack();
+        buf.set_compaction_policy(CompactionPolicy::Never);
+        buf.push(&[1, 2, 3, 4]).unwrap();
+        buf.skip(2).unwrap();
+
+        assert!(! buf.ensure_remaining_capacity());
+        assert_eq!(buf.data(), &[3, 4]);
+
+        buf.shift();
+        assert!(buf.ensure_remaining_capacity());
+    }
+}
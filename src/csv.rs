@@ -0,0 +1,372 @@
+//! Streaming CSV records as described in RFC 4180. 
+//! Fields are separated by `,`, records end with `\n` or `\r\n` 
+//! and fields containing `,`, `"`, `\r` or `\n` are quoted with `"`.
+
+use crate::{BufferError, BufferReader, BufferWriter};
+
+/// Reads CSV records from a [`BufferReader`]
+pub trait CsvReader: BufferReader {
+
+    /// Reads a complete record and consumes its terminator
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoData`] if the record is not complete yet, the reader is left untouched
+    /// [`BufferError::InvalidCsv`] if the record contains a misplaced quote, the reader is left untouched
+    fn read_record(&self) -> Result<Record<'_>, BufferError> {
+        let src = self.unread();
+        let len = scan_record(src)?;
+
+        self.add_bytes_read(len + 1);
+        let raw = &src[..len];
+        let raw = raw.strip_suffix(b"\r").unwrap_or(raw);
+        Ok(Record { raw })
+    }
+
+    /// Returns an iterator over the complete records. 
+    /// The iterator ends if there is no complete record left or returns [`BufferError::InvalidCsv`] once.
+    fn records(&self) -> Records<'_, Self> where Self: Sized {
+        Records { reader: self, done: false }
+    }
+}
+
+impl <R: BufferReader> CsvReader for R {}
+
+/// Writes CSV records to a [`BufferWriter`]
+pub trait CsvWriter: BufferWriter {
+
+    /// Writes `fields` as a record terminated with `\r\n` and returns the number of bytes written. 
+    /// Fields are quoted if required. The record is committed as a whole or not at all.
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoCapacity`] if the record does not fit into the writer
+    fn write_record<F: AsRef<[u8]>>(&mut self, fields: &[F]) -> Result<usize, BufferError> {
+        let len = fields.iter()
+            .map(|f| encoded_len(f.as_ref()))
+            .sum::<usize>() + fields.len().saturating_sub(1) + 2;
+        if self.remaining_capacity() < len {
+            return Err(BufferError::NoCapacity);
+        }
+
+        let mut offset = 0;
+        for (i, field) in fields.iter().enumerate() {
+            if i > 0 {
+                self[offset] = b',';
+                offset += 1;
+            }
+            offset += encode(field.as_ref(), &mut self[offset..]);
+        }
+        self[offset..offset + 2].copy_from_slice(b"\r\n");
+
+        self.commit(len)?;
+        Ok(len)
+    }
+}
+
+impl <W: BufferWriter> CsvWriter for W {}
+
+/// An iterator over the records of a [`BufferReader`] created by [`CsvReader::records`]
+pub struct Records<'a, R: BufferReader> {
+    reader: &'a R,
+    done: bool,
+}
+
+impl <'a, R: BufferReader> Iterator for Records<'a, R> {
+    type Item = Result<Record<'a>, BufferError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.reader.read_record() {
+            Ok(record) => Some(Ok(record)),
+            Err(e) => {
+                self.done = true;
+                match e {
+                    BufferError::InvalidCsv => Some(Err(e)),
+                    _ => None,
+                }
+            },
+        }
+    }
+}
+
+/// A complete CSV record without its terminator
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Record<'a> {
+    raw: &'a [u8],
+}
+
+impl <'a> Record<'a> {
+
+    /// Returns the bytes of the record as read
+    pub fn raw(&self) -> &'a [u8] {
+        self.raw
+    }
+
+    /// Returns an iterator over the fields of the record
+    pub fn fields(&self) -> Fields<'a> {
+        Fields { rest: Some(self.raw) }
+    }
+}
+
+/// An iterator over the fields of a [`Record`]
+pub struct Fields<'a> {
+    rest: Option<&'a [u8]>,
+}
+
+impl <'a> Iterator for Fields<'a> {
+    type Item = Field<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rest = self.rest?;
+
+        // The record was checked by `scan_record`
+        let (field, end) = if rest.first() == Some(&b'"') {
+            // A quote at the end of the record is the closing quote
+            let close = closing_quote(&rest[1..]).unwrap_or(rest.len() - 2);
+            (Field { raw: &rest[1..close + 1], quoted: true }, close + 2)
+        } else {
+            let end = rest.iter().position(|b| *b == b',').unwrap_or(rest.len());
+            (Field { raw: &rest[..end], quoted: false }, end)
+        };
+
+        self.rest = rest.get(end + 1..);
+        Some(field)
+    }
+}
+
+/// A field of a [`Record`]. 
+/// The content of quoted fields still contains escaped quotes (`""`), see [`Field::copy_to`] and [`Field::bytes`].
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct Field<'a> {
+    raw: &'a [u8],
+    quoted: bool,
+}
+
+impl <'a> Field<'a> {
+
+    /// Returns the content of the field without the surrounding quotes
+    pub fn raw(&self) -> &'a [u8] {
+        self.raw
+    }
+
+    /// Returns `true` if the field was quoted
+    pub fn is_quoted(&self) -> bool {
+        self.quoted
+    }
+
+    /// Returns the content if it does not contain escaped quotes
+    pub fn unescaped(&self) -> Option<&'a [u8]> {
+        if self.quoted && self.raw.contains(&b'"') {
+            None
+        } else {
+            Some(self.raw)
+        }
+    }
+
+    /// Returns an iterator over the unescaped bytes of the field
+    pub fn bytes(&self) -> FieldBytes<'a> {
+        FieldBytes { raw: self.raw, quoted: self.quoted }
+    }
+
+    /// Copies the unescaped content to `tgt` and returns its length
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoCapacity`] if `tgt` is too small
+    pub fn copy_to(&self, tgt: &mut [u8]) -> Result<usize, BufferError> {
+        let mut n = 0;
+        for b in self.bytes() {
+            *tgt.get_mut(n).ok_or(BufferError::NoCapacity)? = b;
+            n += 1;
+        }
+        Ok(n)
+    }
+}
+
+/// An iterator over the unescaped bytes of a [`Field`]
+pub struct FieldBytes<'a> {
+    raw: &'a [u8],
+    quoted: bool,
+}
+
+impl <'a> Iterator for FieldBytes<'a> {
+    type Item = u8;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (b, rest) = self.raw.split_first()?;
+        self.raw = match rest {
+            [b'"', rest @ ..] if self.quoted && *b == b'"' => rest,
+            _ => rest,
+        };
+        Some(*b)
+    }
+}
+
+/// Returns the length of the first record in `src` up to the `\n`
+fn scan_record(src: &[u8]) -> Result<usize, BufferError> {
+    let mut i = 0;
+    let mut field_start = true;
+    while i < src.len() {
+        match src[i] {
+            b'\n' => return Ok(i),
+            b',' => field_start = true,
+            b'"' if field_start => {
+                let close = closing_quote(&src[i + 1..]).ok_or(BufferError::NoData)?;
+                i += close + 2;
+                // Only a separator or the end of the record may follow the closing quote
+                match src.get(i..).and_then(|rest| rest.get(..2).or(rest.get(..1))) {
+                    None | Some([]) | Some(b"\r") => return Err(BufferError::NoData),
+                    Some([b',', ..]) | Some([b'\n', ..]) | Some(b"\r\n") => continue,
+                    Some(_) => return Err(BufferError::InvalidCsv),
+                }
+            },
+            b'"' => return Err(BufferError::InvalidCsv),
+            _ => field_start = false,
+        }
+        i += 1;
+    }
+    Err(BufferError::NoData)
+}
+
+/// Returns the index of the closing quote in the content of a quoted field. 
+/// Returns `None` if the content may continue
+fn closing_quote(content: &[u8]) -> Option<usize> {
+    let mut i = 0;
+    while i < content.len() {
+        if content[i] == b'"' {
+            match content.get(i + 1) {
+                Some(b'"') => i += 1,
+                Some(_) => return Some(i),
+                // An escaped quote or the end of the field
+                None => return None,
+            }
+        }
+        i += 1;
+    }
+    None
+}
+
+/// Returns `true` if `field` must be quoted
+fn needs_quotes(field: &[u8]) -> bool {
+    field.iter().any(|b| matches!(b, b',' | b'"' | b'\r' | b'\n'))
+}
+
+/// Returns the length of `field` after quoting
+fn encoded_len(field: &[u8]) -> usize {
+    if needs_quotes(field) {
+        field.len() + field.iter().filter(|b| **b == b'"').count() + 2
+    } else {
+        field.len()
+    }
+}
+
+/// Writes the quoted `field` to `tgt` and returns the number of bytes written
+fn encode(field: &[u8], tgt: &mut [u8]) -> usize {
+    if ! needs_quotes(field) {
+        tgt[..field.len()].copy_from_slice(field);
+        return field.len();
+    }
+
+    let mut n = 0;
+    tgt[n] = b'"';
+    n += 1;
+    for b in field {
+        if *b == b'"' {
+            tgt[n] = b'"';
+            n += 1;
+        }
+        tgt[n] = *b;
+        n += 1;
+    }
+    tgt[n] = b'"';
+    n + 1
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Buffer, BufferError, BufferReader, ReadWrite};
+    use super::{CsvReader, CsvWriter};
+
+    #[test]
+    fn test_read_records() {
+        let mut buf = Buffer::<[u8; 64]>::new_stack();
+        buf.push(b"a,\"b,\"\"c\"\"\",\r\n1,2,3\n").unwrap();
+
+        let reader = buf.create_reader();
+        let mut records = reader.records();
+
+        let record = records.next().unwrap().unwrap();
+        let mut fields = record.fields();
+        assert_eq!(fields.next().unwrap().raw(), b"a");
+        let field = fields.next().unwrap();
+        assert!(field.is_quoted());
+        assert_eq!(field.unescaped(), None);
+        let mut tgt = [0u8; 8];
+        let n = field.copy_to(&mut tgt).unwrap();
+        assert_eq!(&tgt[..n], b"b,\"c\"");
+        assert_eq!(fields.next().unwrap().raw(), b"");
+        assert_eq!(fields.next(), None);
+
+        let record = records.next().unwrap().unwrap();
+        assert_eq!(record.fields().count(), 3);
+        assert!(records.next().is_none());
+    }
+
+    #[test]
+    fn test_record_split_across_fills() {
+        let mut buf = Buffer::<[u8; 32]>::new_stack();
+        buf.push(b"x,\"multi\nline\"").unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(reader.read_record(), Err(BufferError::NoData));
+        assert_eq!(reader.bytes_read(), 0);
+        drop(reader);
+
+        buf.push(b"\"\"\r").unwrap();
+        let reader = buf.create_reader();
+        assert_eq!(reader.read_record(), Err(BufferError::NoData));
+        drop(reader);
+
+        buf.push(b"\n").unwrap();
+        let reader = buf.create_reader();
+        let record = reader.read_record().unwrap();
+        let field = record.fields().nth(1).unwrap();
+        assert!(field.bytes().eq(b"multi\nline\"".iter().copied()));
+        drop(reader);
+
+        assert!(buf.data().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_quote() {
+        let mut buf = Buffer::<[u8; 32]>::new_stack();
+        buf.push(b"a\"b\n\"a\"b\n").unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(reader.read_record(), Err(BufferError::InvalidCsv));
+        reader.add_bytes_read(4);
+        assert_eq!(reader.read_record(), Err(BufferError::InvalidCsv));
+    }
+
+    #[test]
+    fn test_write_record() {
+        let mut buf = Buffer::<[u8; 32]>::new_stack();
+        let mut writer = buf.create_writer();
+        writer.write_record(&["a", "b,c", "say \"hi\""]).unwrap();
+        assert_eq!(writer.write_record(&["0123456789"]), Err(BufferError::NoCapacity));
+        drop(writer);
+
+        assert_eq!(buf.data(), b"a,\"b,c\",\"say \"\"hi\"\"\"\r\n");
+
+        let reader = buf.create_reader();
+        let record = reader.read_record().unwrap();
+        let mut tgt = [0u8; 16];
+        let field = record.fields().nth(2).unwrap();
+        let n = field.copy_to(&mut tgt).unwrap();
+        assert_eq!(&tgt[..n], b"say \"hi\"");
+    }
+}
@@ -0,0 +1,334 @@
+use core::fmt::{self, Arguments};
+
+use thiserror::Error;
+
+use crate::{Buffer, BufferCapacity, BufferIndex, BufferSource, BufferWriter, CompactionPolicy, Write};
+#[cfg(feature = "ufmt")]
+use crate::BufferError;
+
+/// Error returned by [`Buffer::format_into`]
+#[derive(Error, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FormatError {
+
+    /// The formatted output did not fit into the buffer. 
+    /// `needed` is the length of the whole formatted output
+    #[error("Error formatting into buffer: {needed} bytes needed")]
+    NoCapacity { needed: usize },
+
+    /// A formatting trait implementation returned an error
+    #[error("Error formatting into buffer: formatter error")]
+    Fmt,
+}
+
+impl <T: BufferSource, I: BufferIndex> Buffer<T, I> {
+
+    /// Calls `f` and restores the read and write position if it returns an error. 
+    /// This makes a sequence of writes like a `write!` or `uwrite!` invocation atomic, 
+    /// bytes consumed by `f` are readable again after an error.
+    /// 
+    /// Dead capacity is reclaimed before `f` is called if the [`CompactionPolicy`] allows it. 
+    /// While `f` runs the buffer is not compacted, so `f` must not call [`Buffer::shift`] or [`Buffer::reset`].
+    /// 
+    /// ```rust
+    ///     use embytes_buffer::{Buffer, BufferError};
+    /// 
+    ///     let mut buf = Buffer::<[u8; 4]>::new_stack();
+    ///     let result = buf.transaction(|buf| {
+    ///         buf.push(b"abc")?;
+    ///         buf.push(b"de")
+    ///     });
+    /// 
+    ///     assert_eq!(result, Err(BufferError::NoCapacity));
+    ///     assert!(buf.data().is_empty());
+    /// ```
+    pub fn transaction<R, E>(&mut self, f: impl FnOnce(&mut Self) -> Result<R, E>) -> Result<R, E> {
+        if self.rpos() > 0 && self.compaction != CompactionPolicy::Never {
+            self.shift();
+        }
+
+        // A shift in `f` would overwrite the bytes of the saved positions
+        let policy = self.compaction;
+        self.compaction = CompactionPolicy::Never;
+        let (read_position, write_position) = (self.read_position, self.write_position);
+
+        let result = f(self);
+        self.compaction = policy;
+        if result.is_err() {
+            self.read_position = read_position;
+            self.write_position = write_position;
+        }
+        result
+    }
+
+    /// Writes the formatted `args` to the buffer as a whole and returns the number of bytes written
+    /// 
+    /// ```rust
+    ///     use embytes_buffer::{Buffer, FormatError};
+    /// 
+    ///     let mut buf = Buffer::<[u8; 4]>::new_stack();
+    ///     assert_eq!(buf.format_into(format_args!("{}", 42)), Ok(2));
+    ///     assert_eq!(buf.format_into(format_args!("{}", 1234)), Err(FormatError::NoCapacity { needed: 4 }));
+    ///     assert_eq!(buf.data(), b"42");
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// [`FormatError::NoCapacity`] if the output does not fit, nothing is written
+    /// [`FormatError::Fmt`] if a formatting trait implementation returned an error, nothing is written
+    pub fn format_into(&mut self, args: Arguments<'_>) -> Result<usize, FormatError> {
+        let len = self.wpos() - self.rpos();
+        match fmt::Write::write_fmt(self, args) {
+            Ok(()) => Ok(self.wpos() - self.rpos() - len),
+            Err(_) => {
+                let mut counter = Counter(0);
+                fmt::Write::write_fmt(&mut counter, args).map_err(|_| FormatError::Fmt)?;
+                let available = if self.may_shift_for(counter.0) {
+                    self.capacity() - len
+                } else {
+                    self.remaining_capacity()
+                };
+                if counter.0 > available {
+                    Err(FormatError::NoCapacity { needed: counter.0 })
+                } else {
+                    Err(FormatError::Fmt)
+                }
+            }
+        }
+    }
+}
+
+/// Counts the bytes of a formatted output
+struct Counter(usize);
+
+impl fmt::Write for Counter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+/// A whole `write!` invocation is written or nothing is written
+impl <T: BufferSource, I: BufferIndex> fmt::Write for Buffer<T, I> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push(s.as_bytes()).map_err(|_| fmt::Error)
+    }
+
+    fn write_fmt(&mut self, args: Arguments<'_>) -> fmt::Result {
+        self.transaction(|buf| fmt::write(buf, args))
+    }
+}
+
+/// A whole `write!` invocation is committed or nothing is committed
+impl <'a, T: BufferSource, I: BufferIndex> fmt::Write for Write<'a, T, I> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        if self.remaining_capacity() < s.len() {
+            return Err(fmt::Error);
+        }
+
+        self[..s.len()].copy_from_slice(s.as_bytes());
+        self.commit(s.len()).map_err(|_| fmt::Error)
+    }
+
+    fn write_fmt(&mut self, args: Arguments<'_>) -> fmt::Result {
+        let cap = self.remaining_capacity();
+        let mut tgt = SliceWriter::new(&mut self[..cap]);
+        fmt::write(&mut tgt, args)?;
+        let n = tgt.len();
+        self.commit(n).map_err(|_| fmt::Error)
+    }
+}
+
+/// Writes formatted output to a slice and fails if it does not fit
+struct SliceWriter<'a> {
+    tgt: &'a mut [u8],
+    len: usize,
+}
+
+impl <'a> SliceWriter<'a> {
+    fn new(tgt: &'a mut [u8]) -> Self {
+        Self { tgt, len: 0 }
+    }
+
+    /// Returns the number of bytes written
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+impl <'a> fmt::Write for SliceWriter<'a> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let tgt = self.tgt.get_mut(self.len..self.len + s.len()).ok_or(fmt::Error)?;
+        tgt.copy_from_slice(s.as_bytes());
+        self.len += s.len();
+        Ok(())
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl <T: BufferSource, I: BufferIndex> Buffer<T, I> {
+
+    /// Returns a writer for a single `uwrite!` invocation that writes the whole output or nothing, 
+    /// see [`AtomicWriter`]
+    pub fn atomic(&mut self) -> AtomicWriter<'_, T, I> {
+        let len = self.wpos() - self.rpos();
+        AtomicWriter { buffer: self, len, failed: false }
+    }
+}
+
+/// A [`ufmt_write::uWrite`] implementation that discards everything written through it if a write fails. 
+/// 
+/// `uwrite!` writes the output with a separate `write_str` for every piece. 
+/// Writing through an [`AtomicWriter`] created with [`Buffer::atomic`] makes a whole invocation atomic: 
+/// `uwrite!(buf.atomic(), "{}-{}", a, b)`
+/// 
+/// After a failed write all further writes fail, so use a new writer for every invocation.
+#[cfg(feature = "ufmt")]
+pub struct AtomicWriter<'a, T: BufferSource, I: BufferIndex = usize> {
+    buffer: &'a mut Buffer<T, I>,
+    len: usize,
+    failed: bool,
+}
+
+#[cfg(feature = "ufmt")]
+impl <'a, T: BufferSource, I: BufferIndex> ufmt_write::uWrite for AtomicWriter<'a, T, I> {
+    type Error = BufferError;
+
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        if self.failed {
+            return Err(BufferError::NoCapacity);
+        }
+
+        self.buffer.push(s.as_bytes()).inspect_err(|_| {
+            // A shift moves the readable bytes but keeps their length
+            self.buffer.set_wpos(self.buffer.rpos() + self.len);
+            self.failed = true;
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::fmt::Write;
+
+    use crate::{Buffer, BufferError, BufferWriter, CompactionPolicy, FormatError, UninitArray};
+
+    #[test]
+    fn test_write_rolls_back() {
+        let mut buf = Buffer::<[u8; 8]>::new_stack();
+        write!(buf, "{}-{}", 12, 34).unwrap();
+        assert_eq!(buf.data(), b"12-34");
+
+        assert!(write!(buf, "{}-{}", 5, 678).is_err());
+        assert_eq!(buf.data(), b"12-34");
+    }
+
+    #[test]
+    fn test_writer_rolls_back() {
+        let mut buf = Buffer::<[u8; 8]>::new_stack();
+        let mut writer = buf.create_writer();
+        write!(writer, "{}", 1).unwrap();
+        assert!(write!(writer, "{}-{}", 2, 345678).is_err());
+        assert_eq!(writer.remaining_capacity(), 7);
+        drop(writer);
+
+        assert_eq!(buf.data(), b"1");
+    }
+
+    #[test]
+    fn test_format_into_needed() {
+        let mut buf = Buffer::<[u8; 8]>::new_stack();
+        buf.push(b"abcd").unwrap();
+        buf.skip(2).unwrap();
+
+        // Uses the dead capacity
+        assert_eq!(buf.format_into(format_args!("{}", 123456)), Ok(6));
+        assert_eq!(buf.format_into(format_args!("{:>4}", 1)), Err(FormatError::NoCapacity { needed: 4 }));
+        assert_eq!(buf.data(), b"cd123456");
+    }
+
+    #[test]
+    fn test_transaction_restores_consumed() {
+        let mut buf = Buffer::<[u8; 4]>::new_stack();
+        buf.push(b"abcd").unwrap();
+        let result = buf.transaction(|buf| {
+            buf.skip(2)?;
+            buf.push(b"xyz")
+        });
+        assert_eq!(result, Err(BufferError::NoCapacity));
+        assert_eq!(buf.data(), b"abcd");
+
+        let mut buf = Buffer::<UninitArray<4>>::new_uninit();
+        buf.push(b"abcd").unwrap();
+        let result = buf.transaction(|buf| {
+            buf.skip(2)?;
+            buf.push(b"xyz")
+        });
+        assert_eq!(result, Err(BufferError::NoCapacity));
+        assert_eq!(buf.data(), b"abcd");
+    }
+
+    #[test]
+    fn test_transaction_after_skip() {
+        let mut buf = Buffer::<[u8; 4]>::new_stack();
+        buf.push(b"abcd").unwrap();
+        buf.skip(2).unwrap();
+        assert_eq!(buf.transaction(|buf| buf.push(b"xyz")), Err(BufferError::NoCapacity));
+        assert_eq!(buf.data(), b"cd");
+
+        let mut buf = Buffer::<UninitArray<4>>::new_uninit();
+        buf.push(b"abcd").unwrap();
+        buf.skip(2).unwrap();
+        assert_eq!(buf.transaction(|buf| buf.push(b"xyz")), Err(BufferError::NoCapacity));
+        assert_eq!(buf.data(), b"cd");
+    }
+
+    #[test]
+    fn test_format_into_needed_never_compact() {
+        let mut buf = Buffer::<[u8; 8]>::new_stack();
+        buf.set_compaction_policy(CompactionPolicy::Never);
+        buf.push(b"abcd").unwrap();
+        buf.skip(2).unwrap();
+
+        // The dead capacity cannot be used
+        assert_eq!(buf.format_into(format_args!("{}", 123456)), Err(FormatError::NoCapacity { needed: 6 }));
+        assert_eq!(buf.format_into(format_args!("{}", 1234)), Ok(4));
+        assert_eq!(buf.data(), b"cd1234");
+    }
+
+    #[test]
+    fn test_writer_as_fmt_write() {
+        fn write_id(w: &mut impl Write, id: u32) -> core::fmt::Result {
+            write!(w, "id={}", id)
+        }
+
+        let mut buf = Buffer::<[u8; 8]>::new_stack();
+        let mut writer = buf.create_writer();
+        write_id(&mut writer, 42).unwrap();
+        assert!(write_id(&mut writer, 123).is_err());
+        drop(writer);
+
+        assert_eq!(buf.data(), b"id=42");
+    }
+
+    #[cfg(feature = "ufmt")]
+    #[test]
+    fn test_uwrite_atomic() {
+        use ufmt_write::uWrite;
+
+        let mut buf = Buffer::<[u8; 4]>::new_stack();
+        buf.push(b"ab").unwrap();
+
+        // Writes the pieces like `uwrite!(buf.atomic(), "{}{}", "c", "de")`
+        let mut writer = buf.atomic();
+        writer.write_str("c").unwrap();
+        assert_eq!(writer.write_str("de"), Err(crate::BufferError::NoCapacity));
+        assert_eq!(writer.write_str("d"), Err(crate::BufferError::NoCapacity));
+        assert_eq!(buf.data(), b"ab");
+
+        let mut writer = buf.atomic();
+        writer.write_str("c").unwrap();
+        writer.write_str("d").unwrap();
+        assert_eq!(buf.data(), b"abcd");
+    }
+}
@@ -0,0 +1,197 @@
+use std::{io, pin::Pin, task::{Context, Poll}};
+
+use futures_io::{AsyncBufRead, AsyncRead, AsyncWrite};
+
+use crate::{AsyncBuffer, BufferIndex, BufferSource};
+
+impl <T: BufferSource, I: BufferIndex> AsyncRead for AsyncBuffer<T, I> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_read_into(cx, buf)
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> AsyncWrite for AsyncBuffer<T, I> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_from(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> AsyncBufRead for AsyncBuffer<T, I> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        self.get_mut().poll_fill_local(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().consume_local(amt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{executor::LocalPool, task::LocalSpawnExt, AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, StreamExt};
+
+    use crate::{AsyncBuffer, Buffer, SmallBuffer, UninitArray};
+
+    #[test]
+    fn test_futures_read_write() {
+        let mut pool = LocalPool::new();
+
+        let mut writer = AsyncBuffer::new(Buffer::new_heap(8));
+        let mut reader = writer.reader();
+
+        let data: Vec<u8> = (0..200).collect();
+        let expected = data.clone();
+
+        pool.spawner().spawn_local(async move {
+            writer.write_all(&data).await.unwrap();
+            AsyncWriteExt::close(&mut writer).await.unwrap();
+        }).unwrap();
+
+        let received = pool.run_until(async move {
+            let mut received = Vec::new();
+            reader.read_to_end(&mut received).await.unwrap();
+            received
+        });
+
+        assert_eq!(received, expected);
+    }
+
+    #[test]
+    fn test_futures_pending_on_empty() {
+        let mut pool = LocalPool::new();
+        let mut reader = AsyncBuffer::new(Buffer::new_heap(8));
+        let mut writer = reader.clone();
+
+        let read = pool.spawner().spawn_local_with_handle(async move {
+            let mut buf = [0u8; 4];
+            let n = reader.read(&mut buf).await.unwrap();
+            buf[..n].to_vec()
+        }).unwrap();
+
+        // The reader is pending until data is written
+        pool.run_until_stalled();
+
+        pool.run_until(async move {
+            writer.write_all(&[1, 2]).await.unwrap();
+        });
+        assert_eq!(pool.run_until(read), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_futures_multiple_pending_readers() {
+        let mut pool = LocalPool::new();
+        let mut writer = AsyncBuffer::new(Buffer::new_heap(8));
+
+        let reads: Vec<_> = (0..2).map(|_| {
+            let mut reader = writer.reader();
+            pool.spawner().spawn_local_with_handle(async move {
+                let mut buf = [0u8; 1];
+                reader.read(&mut buf).await.unwrap()
+            }).unwrap()
+        }).collect();
+
+        // Both readers are pending before any data is written
+        pool.run_until_stalled();
+
+        pool.run_until(async move {
+            writer.write_all(&[1, 2]).await.unwrap();
+        });
+        for read in reads {
+            assert_eq!(pool.run_until(read), 1);
+        }
+    }
+
+    #[test]
+    fn test_futures_buf_read() {
+        let mut pool = LocalPool::new();
+
+        let mut writer = AsyncBuffer::new(Buffer::new_heap(8));
+        let reader = writer.reader();
+
+        pool.spawner().spawn_local(async move {
+            writer.write_all(b"one\ntwo\n").await.unwrap();
+            AsyncWriteExt::close(&mut writer).await.unwrap();
+        }).unwrap();
+
+        let lines: Vec<String> = pool.run_until(async move {
+            reader.lines().map(|l| l.unwrap()).collect().await
+        });
+
+        assert_eq!(lines, vec!["one", "two"]);
+    }
+
+    #[test]
+    fn test_futures_writer_drop_closes() {
+        let mut pool = LocalPool::new();
+
+        let mut writer = AsyncBuffer::new(Buffer::new_heap(8));
+        let mut second = writer.clone();
+        let mut reader = writer.reader();
+
+        pool.spawner().spawn_local(async move {
+            writer.write_all(&[1, 2]).await.unwrap();
+            second.write_all(&[3]).await.unwrap();
+        }).unwrap();
+
+        // Dropping all writer handles without close ends the reader with EOF
+        let received = pool.run_until(async move {
+            let mut received = Vec::new();
+            reader.read_to_end(&mut received).await.unwrap();
+            assert!(reader.is_closed());
+            received
+        });
+
+        assert_eq!(received, [1, 2, 3]);
+    }
+
+    #[test]
+    fn test_futures_reader_cannot_write() {
+        let mut pool = LocalPool::new();
+        let writer = AsyncBuffer::new(Buffer::new_heap(8));
+        let mut reader = writer.reader();
+
+        let err = pool.run_until(reader.write(&[1])).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::PermissionDenied);
+        assert!(! writer.is_closed());
+
+        // Dropping a reader handle does not close the buffer
+        drop(reader);
+        assert!(! writer.is_closed());
+    }
+
+    #[test]
+    fn test_futures_small_and_uninit_buffer() {
+        let mut pool = LocalPool::new();
+
+        let mut writer = AsyncBuffer::new(SmallBuffer::<8, u8>::new_stack());
+        let mut reader = writer.reader();
+        let received = pool.run_until(async move {
+            writer.write_all(&[1, 2]).await.unwrap();
+            drop(writer);
+            let mut received = Vec::new();
+            reader.read_to_end(&mut received).await.unwrap();
+            received
+        });
+        assert_eq!(received, [1, 2]);
+
+        let mut writer = AsyncBuffer::new(Buffer::<UninitArray<8>>::new_uninit());
+        let mut reader = writer.reader();
+        let received = pool.run_until(async move {
+            writer.write_all(&[3]).await.unwrap();
+            drop(writer);
+            let mut received = Vec::new();
+            reader.read_to_end(&mut received).await.unwrap();
+            received
+        });
+        assert_eq!(received, [3]);
+    }
+}
@@ -0,0 +1,87 @@
+use core::convert::Infallible;
+
+use embedded_hal_nb::{nb, serial::{ErrorType, Read, Write}};
+
+use crate::{Buffer, BufferError, BufferIndex};
+
+/// A [`Buffer`] can be used as a serial port, e.g. as a loopback or a mock UART in tests. 
+/// [`BufferError::NoData`] and [`BufferError::NoCapacity`] are mapped to [`nb::Error::WouldBlock`].
+impl <T: AsMut<[u8]> + AsRef<[u8]>, I: BufferIndex> ErrorType for Buffer<T, I> {
+    type Error = Infallible;
+}
+
+impl <T: AsMut<[u8]> + AsRef<[u8]>, I: BufferIndex> Read<u8> for Buffer<T, I> {
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        let mut word = [0u8; 1];
+        match self.read_base(&mut word) {
+            Ok(_) => Ok(word[0]),
+            Err(BufferError::NoData) => Err(nb::Error::WouldBlock),
+            Err(e) => {
+                panic!("unexpected error reading from buffer: {}", e);
+            }
+        }
+    }
+}
+
+impl <T: AsMut<[u8]> + AsRef<[u8]>, I: BufferIndex> Write<u8> for Buffer<T, I> {
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        match self.write_base(&[word]) {
+            Ok(_) => Ok(()),
+            Err(BufferError::NoCapacity) => Err(nb::Error::WouldBlock),
+            Err(e) => {
+                panic!("unexpected error writing to buffer: {}", e);
+            }
+        }
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use embedded_hal_nb::{nb, serial::{Read, Write}};
+
+    use crate::Buffer;
+
+    /// A driver written against the serial traits that sends a command and reads the reply
+    fn send_command<S: Read + Write>(serial: &mut S, command: &[u8], reply: &mut [u8]) -> usize {
+        for word in command {
+            nb::block!(serial.write(*word)).unwrap();
+        }
+        nb::block!(serial.flush()).unwrap();
+
+        let mut n = 0;
+        while n < reply.len() {
+            match serial.read() {
+                Ok(word) => {
+                    reply[n] = word;
+                    n += 1;
+                },
+                Err(nb::Error::WouldBlock) => break,
+                Err(nb::Error::Other(_)) => unreachable!(),
+            }
+        }
+        n
+    }
+
+    #[test]
+    fn test_loopback() {
+        let mut serial = Buffer::<[u8; 8]>::new_stack();
+        let mut reply = [0u8; 8];
+
+        let n = send_command(&mut serial, b"AT\r\n", &mut reply);
+        assert_eq!(&reply[..n], b"AT\r\n");
+    }
+
+    #[test]
+    fn test_would_block() {
+        let mut serial = Buffer::<[u8; 1]>::new_stack();
+
+        assert_eq!(Read::read(&mut serial), Err(nb::Error::WouldBlock));
+        Write::write(&mut serial, 1).unwrap();
+        assert_eq!(Write::write(&mut serial, 2), Err(nb::Error::WouldBlock));
+        assert_eq!(Read::read(&mut serial), Ok(1));
+    }
+}
@@ -0,0 +1,64 @@
+use core::fmt::Debug;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Integer type used to store the read and write positions of a [`crate::Buffer`].
+/// 
+/// Using a smaller index type like [`u8`] or [`u16`] reduces the size of a buffer 
+/// on targets with little RAM. The capacity of the buffer is limited to [`BufferIndex::MAX`].
+pub trait BufferIndex: private::Sealed + Copy + Debug + PartialEq + PartialOrd {
+
+    /// The index `0`
+    const ZERO: Self;
+
+    /// The maximum capacity a buffer using this index type can have
+    const MAX: usize;
+
+    /// Converts the index to [`usize`]
+    fn to_usize(self) -> usize;
+
+    /// Converts a [`usize`] to the index type. `value` must not be greater than [`BufferIndex::MAX`]
+    fn from_usize(value: usize) -> Self;
+}
+
+macro_rules! impl_buffer_index {
+    ($($t:ty),*) => {
+        $(
+            impl private::Sealed for $t {}
+
+            impl BufferIndex for $t {
+                const ZERO: Self = 0;
+                const MAX: usize = <$t>::MAX as usize;
+
+                fn to_usize(self) -> usize {
+                    self as usize
+                }
+
+                fn from_usize(value: usize) -> Self {
+                    debug_assert!(value <= <Self as BufferIndex>::MAX);
+                    value as $t
+                }
+            }
+        )*
+    };
+}
+
+impl_buffer_index!(u8, u16, u32, usize);
+
+/// Converts an index to [`usize`] in a `const fn`, where [`BufferIndex::to_usize`] can not be called
+pub(crate) const fn index_to_usize<I: BufferIndex>(index: &I) -> usize {
+    let ptr = index as *const I;
+
+    // SAFETY: `BufferIndex` is sealed and only implemented for unsigned integers, 
+    // so the size of `I` determines the integer type
+    unsafe {
+        match core::mem::size_of::<I>() {
+            1 => *(ptr as *const u8) as usize,
+            2 => *(ptr as *const u16) as usize,
+            4 => *(ptr as *const u32) as usize,
+            _ => *(ptr as *const usize),
+        }
+    }
+}
@@ -1,7 +1,7 @@
 use serde::Deserialize;
 use serde_json_core::{from_slice, to_slice};
 
-use crate::{Buffer, BufferError, BufferReader, BufferWriter};
+use crate::{Buffer, BufferError, BufferIndex, BufferReader, BufferWriter};
 
 pub trait JsonWriter {
     fn serialize_json<T: serde::Serialize>(&mut self, src: &T) -> Result<usize, BufferError>;
@@ -18,15 +18,16 @@ impl <'a, W: BufferWriter> JsonWriter for W {
     }
 }
 
-impl <S: AsMut<[u8]> + AsRef<[u8]>> JsonWriter for Buffer<S> {
+impl <S: AsMut<[u8]> + AsRef<[u8]>, I: BufferIndex> JsonWriter for Buffer<S, I> {
     fn serialize_json<T: serde::Serialize>(&mut self, src: &T) -> Result<usize, BufferError> {
         
-        let tgt = &mut self.source.as_mut()[self.write_position..];
+        let write_position = self.wpos();
+        let tgt = &mut self.source.as_mut()[write_position..];
         
         let n = to_slice(src, tgt)
             .map_err(|_e| BufferError::NoCapacity)?;
 
-        self.write_position += n;
+        self.set_wpos(write_position + n);
         Ok(n)
     }
 }
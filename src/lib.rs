@@ -9,6 +9,101 @@ pub use write::*;
 mod read;
 pub use read::*;
 
+mod compaction;
+pub use compaction::*;
+
+mod index;
+pub use index::*;
+
+mod source;
+pub use source::*;
+
+mod uninit;
+pub use uninit::*;
+
+mod search;
+pub use search::*;
+
+mod text;
+pub use text::*;
+
+mod format;
+pub use format::*;
+
+mod num;
+pub use num::*;
+
+pub mod csv;
+
+pub mod at;
+
+pub mod cli;
+
+#[cfg(feature = "embedded")]
+mod buffered_reader;
+#[cfg(feature = "embedded")]
+pub use buffered_reader::*;
+
+#[cfg(feature = "embedded")]
+mod buffered_writer;
+#[cfg(feature = "embedded")]
+pub use buffered_writer::*;
+
+#[cfg(feature = "async")]
+mod pump;
+#[cfg(feature = "async")]
+pub use pump::*;
+
+#[cfg(all(feature = "std", feature = "embedded"))]
+mod adapters;
+#[cfg(all(feature = "std", feature = "embedded"))]
+pub use adapters::*;
+
+#[cfg(feature = "std")]
+mod shared;
+#[cfg(feature = "std")]
+pub use shared::*;
+
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+mod async_buffer;
+#[cfg(any(feature = "tokio", feature = "futures-io"))]
+pub use async_buffer::*;
+
+#[cfg(feature = "futures-io")]
+mod futures_io;
+
+#[cfg(feature = "tokio")]
+mod tokio_io;
+
+#[cfg(feature = "tokio")]
+pub mod codec;
+
+#[cfg(any(feature = "nom", feature = "winnow"))]
+mod parse;
+#[cfg(any(feature = "nom", feature = "winnow"))]
+pub use parse::*;
+
+#[cfg(feature = "nom")]
+mod nom_parse;
+#[cfg(feature = "nom")]
+pub use nom_parse::*;
+
+#[cfg(feature = "winnow")]
+mod winnow_parse;
+#[cfg(feature = "winnow")]
+pub use winnow_parse::*;
+
+#[cfg(feature = "bytes")]
+mod bytes_buf;
+
+#[cfg(feature = "embedded-hal-nb")]
+mod hal_nb;
+
+#[cfg(feature = "critical-section")]
+mod sync;
+#[cfg(feature = "critical-section")]
+pub use sync::*;
+
 #[cfg(feature = "serde")]
 pub mod json;
 
@@ -32,12 +127,76 @@ pub enum BufferError {
     #[error("Error reading from buffer: no remaining data")]
     NoData,
 
+    /// A slot passed to [`Write::fill_slot`] does not belong to the writer 
+    /// or the data does not match the size of the slot. 
+    /// Also returned by [`BufferWriter::commit`] while a slot reserved with [`Write::reserve_slot`] is not filled
+    #[error("Error with reserved slot: invalid slot, size mismatch or unfilled slot")]
+    InvalidSlot,
+
+    /// No delimiter was found within the maximum length passed to a delimiter search like [`BufferReader::read_until_max`]
+    #[error("Error reading from buffer: no delimiter within the maximum length")]
+    MaxLengthExceeded,
+
+    /// The data read by a [`TextReader`] starts with an invalid UTF-8 sequence
+    #[error("Error reading text from buffer: invalid UTF-8")]
+    InvalidUtf8,
+
+    /// A number could not be parsed or written by [`NumberReader`] or [`NumberWriter`]
+    #[error("Error reading or writing number: invalid number")]
+    InvalidNumber,
+
+    /// A CSV record read by [`csv::CsvReader`] contains a misplaced quote
+    #[error("Error reading CSV record: misplaced quote")]
+    InvalidCsv,
 
     #[cfg(feature = "serde")]
     #[error("Error while deserializing JSON")]
     JsonDeserialize(serde_json_core::de::Error)
 }
 
+/// Error of adapters that move data between a [`Buffer`] and a reader or writer like [`BufferedReader`] and [`BufferedWriter`]
+#[derive(Error, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum BufferedError<E> {
+
+    /// The underlying reader or writer returned an error
+    #[error("Error in the underlying reader or writer")]
+    Io(E),
+
+    /// The buffer returned an error
+    #[error("Error in the buffer: {0}")]
+    Buffer(BufferError),
+
+    /// The underlying reader reached EOF before enaugh data was read
+    #[error("Unexpected end of file")]
+    UnexpectedEof,
+
+    /// The underlying writer did not accept any bytes
+    #[error("The underlying writer accepted no bytes")]
+    WriteZero,
+}
+
+impl <E> From<BufferError> for BufferedError<E> {
+    fn from(value: BufferError) -> Self {
+        Self::Buffer(value)
+    }
+}
+
+#[cfg(feature = "embedded")]
+impl <E: embedded_io::Error> embedded_io::Error for BufferedError<E> {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        use embedded_io::ErrorKind;
+
+        match self {
+            BufferedError::Io(e) => e.kind(),
+            BufferedError::Buffer(BufferError::NoCapacity) => ErrorKind::OutOfMemory,
+            BufferedError::Buffer(_) => ErrorKind::Other,
+            BufferedError::UnexpectedEof => ErrorKind::Other,
+            BufferedError::WriteZero => ErrorKind::WriteZero,
+        }
+    }
+}
+
 
 /// Trait that allows to create a reader and a writer for a buffer.
 /// See [`BufferReader`] adn [`BufferWriter`]
@@ -50,48 +209,116 @@ pub trait ReadWrite {
     fn create_writer<'a>(&'a mut self) -> impl BufferWriter + 'a;
 }
 
+/// Trait to get the capacity of a [`Buffer`] with any [`BufferSource`]. 
+/// 
+/// Buffers backed by arrays, slices and [`UninitArray`] have an inherent `const fn capacity` 
+/// that is used if the type of the source is known.
+pub trait BufferCapacity {
+    /// Returns the length of the undelying buffer
+    fn capacity(&self) -> usize;
+}
+
 /// A buffer that allows reading and writuing bytes [`u8`] from / to an underlying generic source
+/// 
+/// The read and write positions are stored as `I`, see [`BufferIndex`] and [`SmallBuffer`].
 #[derive(Debug)]
-pub struct Buffer<T: AsMut<[u8]> + AsRef<[u8]>> {
+pub struct Buffer<T: BufferSource, I: BufferIndex = usize> {
     pub(crate) source: T,
-    pub(crate) write_position: usize,
-    pub(crate) read_position: usize,
+    pub(crate) write_position: I,
+    pub(crate) read_position: I,
+    pub(crate) compaction: CompactionPolicy,
 }
 
+/// A [`Buffer`] that is backed by an owned [`u8`] array with size `N` and stores its positions as `I`.
+/// 
+/// # Example
+/// 
+/// ```rust
+///     use embytes_buffer::SmallBuffer;
+/// 
+///     let mut buffer = SmallBuffer::<64, u8>::new_stack();
+///     buffer.push(&[1, 2, 3]).unwrap();
+///     assert_eq!(buffer.remaining_len(), 3);
+/// ```
+pub type SmallBuffer<const N: usize, I = u16> = Buffer<[u8; N], I>;
+
 /// Creates a new [`Buffer`] that is backed by an owned [`u8`] array with size `N`
 #[deprecated]
-pub fn new_stack_buffer<const N: usize>() -> Buffer<[u8; N]> {
+pub const fn new_stack_buffer<const N: usize>() -> Buffer<[u8; N]> {
     Buffer::<[u8; N]> {
         source: [0; N],
         read_position: 0,
         write_position: 0,
+        compaction: CompactionPolicy::Always,
     }
 }
 
-impl <const N: usize> Buffer<[u8; N]> {
+impl <const N: usize, I: BufferIndex> Buffer<[u8; N], I> {
+
+    /// The capacity of a [`Buffer`] backed by an [`u8`] array with size `N`.
+    /// Can be used in const contexts without a buffer.
+    pub const CAPACITY: usize = N;
+
+    /// Returns the length of the undelying array
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+
+    /// Creates a new [`Buffer`] that is backed by an owned [`u8`] array with size `N`. 
+    /// Fails to compile if `N` does not fit into the index type `I`.
+    /// 
+    /// ```compile_fail
+    ///     use embytes_buffer::SmallBuffer;
+    /// 
+    ///     let buffer = SmallBuffer::<256, u8>::new_stack();
+    /// ```
+    pub const fn new_stack() -> Self {
+        const { assert!(N <= I::MAX, "the buffer size N does not fit into the index type") };
 
-    /// Creates a new [`Buffer`] that is backed by an owned [`u8`] array with size `N`
-    pub fn new_stack() -> Self {
         Self {
             source: [0; N],
-            read_position: 0,
-            write_position: 0,
+            read_position: I::ZERO,
+            write_position: I::ZERO,
+            compaction: CompactionPolicy::Always,
         }
     }
 }
 
+impl <'a, I: BufferIndex> Buffer<&'a mut [u8], I> {
+
+    /// Returns the length of the undelying slice
+    pub const fn capacity(&self) -> usize {
+        self.source.len()
+    }
+}
+
+impl <'a, const N: usize, I: BufferIndex> Buffer<&'a mut [u8; N], I> {
+
+    /// Returns the length of the undelying array
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
 #[cfg(feature = "std")]
 impl Buffer<Vec<u8>> {
 
-    /// Creates a new [`Buffer`] that is backed by an owned [`Vec<u8>`]
+    /// Creates a new [`Buffer`] that is backed by an owned [`Vec<u8>`]. 
+    /// The bytes are initialized with `0`, see [`Buffer::new_heap_uninit`] to only allocate them.
     pub fn new_heap(size: usize) -> Self {
         Self {
             source: vec![0; size],
             read_position: 0,
             write_position: 0,
+            compaction: CompactionPolicy::Always,
         }
     }
 
+    /// Returns the length of the undelying [`Vec<u8>`]
+    pub fn capacity(&self) -> usize {
+        self.source.len()
+    }
+
     /// Grows the buffer capacity by `grow_by` bytes
     pub fn grow(&mut self, grow_by: usize) {
         self.source.extend(
@@ -112,18 +339,18 @@ impl Buffer<Vec<u8>> {
 }
 
 #[cfg(feature = "defmt")]
-impl <T: AsMut<[u8]> + AsRef<[u8]>> defmt::Format for Buffer<T> {
+impl <T: BufferSource, I: BufferIndex> defmt::Format for Buffer<T, I> {
     fn format(&self, fmt: defmt::Formatter) {
         defmt::write!(fmt, 
             "Buffer(len = {}, cap = {}, rem_cap = {})",
-            self.remaining_len(),
+            self.wpos() - self.rpos(),
             self.capacity(),
             self.remaining_capacity()
         );
     }
 }
 
-impl <T: AsMut<[u8]> + AsRef<[u8]>> Buffer<T> {
+impl <T: BufferSource> Buffer<T> {
 
     /// Create a new buffer from any source 
     /// 
@@ -135,66 +362,146 @@ impl <T: AsMut<[u8]> + AsRef<[u8]>> Buffer<T> {
     ///     let mut bytes = [0; 1024];
     ///     let mut buffer = Buffer::new(&mut bytes);
     /// ```
-    pub fn new(source: T) -> Self {
+    pub const fn new(source: T) -> Self {
         Self {
             source,
             read_position: 0,
             write_position: 0,
+            compaction: CompactionPolicy::Always,
         }
     }
+}
 
-    /// Reset the buffer to its initial state
-    pub fn reset(&mut self) {
-        self.read_position = 0;
-        self.write_position = 0;
+impl <T: BufferSource, I: BufferIndex> Buffer<T, I> {
+
+    /// Create a new buffer from any source that stores its positions as `I`
+    /// 
+    /// # Example
+    /// 
+    /// ```rust
+    ///     use embytes_buffer::Buffer;
+    /// 
+    ///     let mut bytes = [0; 1024];
+    ///     let mut buffer = Buffer::<_, u16>::new_indexed(&mut bytes[..]);
+    /// ```
+    /// 
+    /// # Panics
+    /// 
+    /// If the size of `source` is greater than [`BufferIndex::MAX`]
+    pub fn new_indexed(source: T) -> Self {
+        assert!(source.size() <= I::MAX, "the size of the source does not fit into the index type");
+
+        Self {
+            source,
+            read_position: I::ZERO,
+            write_position: I::ZERO,
+            compaction: CompactionPolicy::Always,
+        }
     }
+}
 
-    /// Returns the length of the undelying buffer
-    pub fn capacity(&self) -> usize {
-        self.source.as_ref().len()
+impl <T: BufferSource, I: BufferIndex> Buffer<T, I> {
+
+    /// Returns the read position as [`usize`]
+    pub(crate) fn rpos(&self) -> usize {
+        self.read_position.to_usize()
     }
 
-    /// Returns the remaining space that can be written to. 
-    /// This method does not perform a [`Buffer::shift`]
-    pub fn remaining_capacity(&self) -> usize {
-        self.capacity() - self.write_position
+    /// Returns the write position as [`usize`]
+    pub(crate) fn wpos(&self) -> usize {
+        self.write_position.to_usize()
     }
 
-    /// returns `true` if there is remaining capacity to write to. 
-    /// is equal to [`Buffer::remaining_capacity`] ` > 0`
-    pub fn has_remaining_capacity(&self) -> bool {
-        self.capacity() > self.write_position
+    pub(crate) fn set_rpos(&mut self, pos: usize) {
+        self.read_position = I::from_usize(pos);
+    }
+
+    pub(crate) fn set_wpos(&mut self, pos: usize) {
+        self.write_position = I::from_usize(pos);
     }
 
     /// Returns the remaining bytes to read
-    pub fn remaining_len(&self) -> usize {
-        self.write_position - self.read_position
+    pub const fn remaining_len(&self) -> usize {
+        index_to_usize(&self.write_position) - index_to_usize(&self.read_position)
     }
 
     /// returns `true` if there are remainng bytes to read. 
-    pub fn has_remaining_len(&self) -> bool {
-        self.write_position > self.read_position
+    pub const fn has_remaining_len(&self) -> bool {
+        index_to_usize(&self.write_position) > index_to_usize(&self.read_position)
     }
 
     /// Returns `true` if there is dead capacity. 
     /// Dead capacity occures when bytes are read from a buffer.
     /// Dead capacity can be regained by using [`Buffer::shift`]
-    pub fn has_dead_capacity(&self) -> bool {
-        self.read_position > 0
+    pub const fn has_dead_capacity(&self) -> bool {
+        index_to_usize(&self.read_position) > 0
+    }
+
+    /// Reset the buffer to its initial state
+    pub const fn reset(&mut self) {
+        self.read_position = I::ZERO;
+        self.write_position = I::ZERO;
+    }
+
+    /// Returns the remaining space that can be written to. 
+    /// This method does not perform a [`Buffer::shift`]
+    pub fn remaining_capacity(&self) -> usize {
+        self.capacity() - self.wpos()
+    }
+
+    /// returns `true` if there is remaining capacity to write to. 
+    /// is equal to [`Buffer::remaining_capacity`] ` > 0`
+    pub fn has_remaining_capacity(&self) -> bool {
+        self.capacity() > self.wpos()
     }
 
     /// Shifts the content of the source left to reuse space of read bytes. 
+    /// Only the readable bytes are copied.
     /// See also [`Buffer::has_dead_capacity`]
     pub fn shift(&mut self) {
-        self.source.as_mut().rotate_left(self.read_position);
-        self.write_position -= self.read_position;
-        self.read_position = 0;
+        let (read_position, write_position) = (self.rpos(), self.wpos());
+        if read_position == 0 {
+            return;
+        }
+
+        self.source.initialized_mut().copy_within(read_position..write_position, 0);
+        self.set_wpos(write_position - read_position);
+        self.read_position = I::ZERO;
+    }
+
+    /// Returns the [`CompactionPolicy`] of the buffer
+    pub const fn compaction_policy(&self) -> CompactionPolicy {
+        self.compaction
+    }
+
+    /// Sets the [`CompactionPolicy`] that decides when the buffer is compacted
+    pub const fn set_compaction_policy(&mut self, policy: CompactionPolicy) {
+        self.compaction = policy;
+    }
+
+    /// Performs a [`Buffer::shift`] if the [`CompactionPolicy`] of the buffer requires it. 
+    /// Returns `true` if the buffer was compacted
+    pub fn compact(&mut self) -> bool {
+        if self.compaction.should_compact(self.capacity(), self.rpos(), self.wpos()) {
+            self.shift();
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns `true` if a shift is allowed to make room for `n` more bytes
+    fn may_shift_for(&self, n: usize) -> bool {
+        self.remaining_capacity() < n 
+            && self.rpos() > 0 
+            && self.compaction != CompactionPolicy::Never
     }
 
     /// Performa s [`Buffer::shift`] if there is no remianing capacity and 
-    /// returns `true` if there is remainig capacity afterwards
+    /// returns `true` if there is remainig capacity afterwards.
+    /// Does not shift if the [`CompactionPolicy`] is [`CompactionPolicy::Never`].
     pub fn ensure_remaining_capacity(&mut self) -> bool {
-        if ! self.has_remaining_capacity() {
+        if self.may_shift_for(1) {
             self.shift();
         }
 
@@ -213,7 +520,7 @@ impl <T: AsMut<[u8]> + AsRef<[u8]>> Buffer<T> {
             return Err(BufferError::ProvidedSliceEmpty);
         }
 
-        if ! self.has_remaining_capacity() && self.has_dead_capacity() {
+        if self.may_shift_for(1) {
             self.shift();
         }
         
@@ -222,19 +529,11 @@ impl <T: AsMut<[u8]> + AsRef<[u8]>> Buffer<T> {
             return Err(BufferError::NoCapacity);
         }
 
-        let tgt = self.source.as_mut();
-        let tgt = &mut tgt[self.write_position..];
-        
-        if cap < buf.len() {
-            tgt.copy_from_slice(&buf[0..cap]);
-            self.write_position += cap;
-            Ok(cap)
-        } else {
-            let tgt = &mut tgt[0..buf.len()];
-            tgt.copy_from_slice(buf);
-            self.write_position += buf.len();
-            Ok(buf.len())
-        }
+        let write_position = self.wpos();
+        let n = cap.min(buf.len());
+        self.source.write_at(write_position, &buf[..n]);
+        self.set_wpos(write_position + n);
+        Ok(n)
     }
 
     /// Base function for implementing readers like [`embedded_io::Read`]
@@ -249,34 +548,42 @@ impl <T: AsMut<[u8]> + AsRef<[u8]>> Buffer<T> {
             return Err(BufferError::ProvidedSliceEmpty);
         }
 
-        let src = self.source.as_ref();
-        let src = &src[self.read_position..self.write_position];
+        let read_position = self.rpos();
+        let src = self.data();
 
         if src.is_empty() {
             return Err(BufferError::NoData);
         }
         else if src.len() > buf.len() {
             buf.copy_from_slice(&src[0..buf.len()]);
-            self.read_position += buf.len();
+            self.set_rpos(read_position + buf.len());
             Ok(buf.len())
         } else {
-            let buf = &mut buf[0..src.len()];
+            let n = src.len();
+            let buf = &mut buf[0..n];
             buf.copy_from_slice(src);
-            self.read_position += src.len();
+            self.set_rpos(read_position + n);
 
-            Ok(src.len())
+            Ok(n)
         }
     }
 
+    /// Creates a writer to write to the buffer like [`ReadWrite::create_writer`]. 
+    /// Returns the [`Write`] itself, so slots can be reserved with [`Write::reserve_slot`].
+    pub fn create_writer(&mut self) -> Write<'_, T, I> {
+        self.compact();
+        Write::new(self)
+    }
+
     /// Creates a reader that ready at most `max_bytes`
-    pub fn create_reader_with_max(&mut self, max_bytes: usize) -> Reader<'_, T> {
+    pub fn create_reader_with_max(&mut self, max_bytes: usize) -> Reader<'_, T, I> {
         Reader::new_with_max(self, max_bytes)
     }
 
     /// Returns a slice containing the readable data
     pub fn data(&self) -> &[u8] {
-        let src = self.source.as_ref();
-        &src[self.read_position..self.write_position]
+        let src = self.source.initialized();
+        &src[self.rpos()..self.wpos()]
     }
 
     /// Skips `n` readable bytes
@@ -285,8 +592,8 @@ impl <T: AsMut<[u8]> + AsRef<[u8]>> Buffer<T> {
     /// 
     /// [`BufferError::NoData`] if `n < self.remaining_len()`
     pub fn skip(&mut self, n: usize) -> Result<(), BufferError> {
-        if self.remaining_len() >= n {
-            self.read_position += n;
+        if self.wpos() - self.rpos() >= n {
+            self.set_rpos(self.rpos() + n);
             Ok(())
         } else {
             Err(BufferError::NoData)
@@ -299,38 +606,65 @@ impl <T: AsMut<[u8]> + AsRef<[u8]>> Buffer<T> {
     /// 
     /// [`BufferError::NoCapacity`] if `buf.len() > self.remaining_capacity()`
     pub fn push(&mut self, buf: &[u8]) -> Result<(), BufferError> {
-        if self.remaining_capacity() < buf.len() && self.has_dead_capacity() {
+        if self.may_shift_for(buf.len()) {
             self.shift();
         }
         
         if self.remaining_capacity() >= buf.len() {
-            let tgt = self.source.as_mut();
-            let tgt = &mut tgt[self.write_position..];
-            let tgt = &mut tgt[0..buf.len()];
-            tgt.copy_from_slice(buf);
-            self.write_position += buf.len();
+            let write_position = self.wpos();
+            self.source.write_at(write_position, buf);
+            self.set_wpos(write_position + buf.len());
             Ok(())
         } else {
             Err(BufferError::NoCapacity)
         }
     }
 
+    /// Appends all provided slices to the buffer as a whole. 
+    /// Either all slices are appended or none
+    /// 
+    /// # Error
+    /// 
+    /// [`BufferError::NoCapacity`] if the total length of `bufs` is greater than [`Buffer::remaining_capacity`]
+    pub fn push_all(&mut self, bufs: &[&[u8]]) -> Result<(), BufferError> {
+        let total: usize = bufs.iter().map(|buf| buf.len()).sum();
+        if self.may_shift_for(total) {
+            self.shift();
+        }
+
+        if self.remaining_capacity() < total {
+            return Err(BufferError::NoCapacity);
+        }
+
+        let mut write_position = self.wpos();
+        for buf in bufs {
+            self.source.write_at(write_position, buf);
+            write_position += buf.len();
+        }
+        self.set_wpos(write_position);
+        Ok(())
+    }
+
+}
+
+impl <T: BufferSource, I: BufferIndex> BufferCapacity for Buffer<T, I> {
+    fn capacity(&self) -> usize {
+        self.source.size()
+    }
 }
 
-impl <T: AsMut<[u8]> + AsRef<[u8]>> ReadWrite for Buffer<T> {
+impl <T: BufferSource, I: BufferIndex> ReadWrite for Buffer<T, I> {
     fn create_reader<'a>(&'a mut self) -> impl BufferReader + 'a {
         Reader::new(self)
     }
 
     fn create_writer<'a>(&'a mut self) -> impl BufferWriter + 'a {
-        self.shift();
-        Write::new(self)
-
+        Buffer::create_writer(self)
     }
 }
 
 #[cfg(feature = "std")]
-impl <T: AsMut<[u8]> + AsRef<[u8]>> std::io::Write for Buffer<T> {
+impl <T: BufferSource, I: BufferIndex> std::io::Write for Buffer<T, I> {
 
     fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
         use std::io::ErrorKind;
@@ -344,13 +678,37 @@ impl <T: AsMut<[u8]> + AsRef<[u8]>> std::io::Write for Buffer<T> {
         }
     }
 
+    fn write_vectored(&mut self, bufs: &[std::io::IoSlice<'_>]) -> std::io::Result<usize> {
+        let mut written = 0;
+        for buf in bufs.iter().filter(|buf| ! buf.is_empty()) {
+            match self.write_base(buf) {
+                Ok(n) => {
+                    written += n;
+                    if n < buf.len() {
+                        break;
+                    }
+                },
+                Err(BufferError::NoCapacity) => break,
+                Err(e) => {
+                    panic!("unexpected error writing to buffer: {}", e);
+                }
+            }
+        }
+
+        if written == 0 && bufs.iter().any(|buf| ! buf.is_empty()) {
+            Err(std::io::ErrorKind::WouldBlock.into())
+        } else {
+            Ok(written)
+        }
+    }
+
     fn flush(&mut self) -> std::io::Result<()> {
         Ok(())
     }
 }
 
 #[cfg(feature = "std")]
-impl <T: AsMut<[u8]> + AsRef<[u8]>> std::io::Read for Buffer<T> {
+impl <T: BufferSource, I: BufferIndex> std::io::Read for Buffer<T, I> {
     
     fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
         use std::io::ErrorKind;
@@ -364,15 +722,41 @@ impl <T: AsMut<[u8]> + AsRef<[u8]>> std::io::Read for Buffer<T> {
             }
         }
     }
+
+    fn read_vectored(&mut self, bufs: &mut [std::io::IoSliceMut<'_>]) -> std::io::Result<usize> {
+        let mut read = 0;
+        for buf in bufs.iter_mut().filter(|buf| ! buf.is_empty()) {
+            match self.read_base(buf) {
+                Ok(n) => {
+                    read += n;
+                    if n < buf.len() {
+                        break;
+                    }
+                },
+                Err(BufferError::NoData) => break,
+                Err(e) => {
+                    panic!("unexpected error reading from buffer: {}", e);
+                }
+            }
+        }
+
+        if read == 0 && bufs.iter().any(|buf| ! buf.is_empty()) {
+            Err(std::io::ErrorKind::WouldBlock.into())
+        } else {
+            Ok(read)
+        }
+    }
 }
 
 #[cfg(feature = "embedded")]
-impl <T: AsMut<[u8]> + AsRef<[u8]>> embedded_io::ErrorType for Buffer<T> {
+impl <T: BufferSource, I: BufferIndex> embedded_io::ErrorType for Buffer<T, I> {
     type Error = embedded_io::ErrorKind;
 }
 
+/// A buffer never blocks: if the buffer is full [`embedded_io::ErrorKind::WriteZero`] is returned 
+/// as required by [`embedded_io::Write::write`].
 #[cfg(feature = "embedded")]
-impl <T: AsMut<[u8]> + AsRef<[u8]>> embedded_io::Write for Buffer<T> {
+impl <T: BufferSource, I: BufferIndex> embedded_io::Write for Buffer<T, I> {
     
     fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
         use embedded_io::ErrorKind;
@@ -380,7 +764,7 @@ impl <T: AsMut<[u8]> + AsRef<[u8]>> embedded_io::Write for Buffer<T> {
         match self.write_base(buf) {
             Ok(n) => Ok(n),
             Err(BufferError::ProvidedSliceEmpty) => Ok(0),
-            Err(BufferError::NoCapacity) => Err(ErrorKind::Other),
+            Err(BufferError::NoCapacity) => Err(ErrorKind::WriteZero),
             Err(e) => {
                 panic!("unexpected error writing to buffer: {}", e);
             }
@@ -392,15 +776,15 @@ impl <T: AsMut<[u8]> + AsRef<[u8]>> embedded_io::Write for Buffer<T> {
     }
 }
 
+/// A buffer never blocks: like the implementation for `&[u8]` an empty buffer is at EOF and returns `Ok(0)`. 
+/// Use [`embedded_io::ReadReady`] to check if there is data to read.
 #[cfg(feature = "embedded")]
-impl <T: AsMut<[u8]> + AsRef<[u8]>> embedded_io::Read for Buffer<T> {
+impl <T: BufferSource, I: BufferIndex> embedded_io::Read for Buffer<T, I> {
     fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
-        use embedded_io::ErrorKind;
-
         match self.read_base(buf) {
             Ok(n) => Ok(n),
             Err(BufferError::ProvidedSliceEmpty) => Ok(0),
-            Err(BufferError::NoData) => Err(ErrorKind::Other),
+            Err(BufferError::NoData) => Ok(0),
             Err(e) => {
                 panic!("unexpected error reading from buffer: {}", e);
             }
@@ -408,12 +792,29 @@ impl <T: AsMut<[u8]> + AsRef<[u8]>> embedded_io::Read for Buffer<T> {
     }
 }
 
-impl <T: AsMut<[u8]> + AsRef<[u8]> + Clone> Clone for Buffer<T> {
+#[cfg(feature = "embedded")]
+impl <T: BufferSource, I: BufferIndex> embedded_io::ReadReady for Buffer<T, I> {
+    fn read_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.wpos() > self.rpos())
+    }
+}
+
+/// Dead capacity counts as writable because writing performs a [`Buffer::shift`] 
+/// unless the [`CompactionPolicy`] is [`CompactionPolicy::Never`].
+#[cfg(feature = "embedded")]
+impl <T: BufferSource, I: BufferIndex> embedded_io::WriteReady for Buffer<T, I> {
+    fn write_ready(&mut self) -> Result<bool, Self::Error> {
+        Ok(self.has_remaining_capacity() || self.may_shift_for(1))
+    }
+}
+
+impl <T: BufferSource + Clone, I: BufferIndex> Clone for Buffer<T, I> {
     fn clone(&self) -> Self {
         Self { 
             source: self.source.clone(), 
             write_position: self.write_position, 
-            read_position: self.read_position
+            read_position: self.read_position,
+            compaction: self.compaction,
         }
     }
 }
@@ -421,7 +822,7 @@ impl <T: AsMut<[u8]> + AsRef<[u8]> + Clone> Clone for Buffer<T> {
 #[cfg(test)]
 mod tests {
 
-    use crate::{Buffer, BufferError};
+    use crate::{Buffer, BufferError, CompactionPolicy, ReadWrite};
 
     #[test]
     fn test_std_write_high_cap() {
@@ -454,6 +855,41 @@ mod tests {
         assert_eq!(&b, &[1, 2, 3, 4])
     }
 
+    #[test]
+    fn test_push_all() {
+        let mut buf = Buffer::<[u8; 8]>::new_stack();
+        buf.push(&[0, 0]).unwrap();
+        buf.skip(2).unwrap();
+
+        // Uses dead capacity
+        buf.push_all(&[&[1, 2], &[], &[3, 4, 5], &[6, 7, 8]]).unwrap();
+        assert_eq!(buf.data(), &[1, 2, 3, 4, 5, 6, 7, 8]);
+
+        // All or nothing
+        buf.skip(2).unwrap();
+        assert_eq!(buf.push_all(&[&[9], &[10, 11]]), Err(BufferError::NoCapacity));
+        assert_eq!(buf.data(), &[3, 4, 5, 6, 7, 8]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_std_vectored() {
+        use std::io::{IoSlice, IoSliceMut, Read, Write};
+
+        let mut buf = Buffer::<[u8; 6]>::new_stack();
+        let n = buf.write_vectored(&[IoSlice::new(&[1, 2]), IoSlice::new(&[]), IoSlice::new(&[3, 4, 5, 6, 7])]).unwrap();
+        assert_eq!(n, 6);
+        assert_eq!(buf.data(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(buf.write_vectored(&[IoSlice::new(&[7])]).unwrap_err().kind(), std::io::ErrorKind::WouldBlock);
+        assert_eq!(buf.write_vectored(&[IoSlice::new(&[])]).unwrap(), 0);
+
+        let (mut a, mut b, mut c) = ([0u8; 1], [0u8; 3], [0u8; 4]);
+        let n = buf.read_vectored(&mut [IoSliceMut::new(&mut a), IoSliceMut::new(&mut b), IoSliceMut::new(&mut c)]).unwrap();
+        assert_eq!(n, 6);
+        assert_eq!((a, b, &c[..2]), ([1], [2, 3, 4], &[5, 6][..]));
+        assert_eq!(buf.read_vectored(&mut [IoSliceMut::new(&mut a)]).unwrap_err().kind(), std::io::ErrorKind::WouldBlock);
+    }
+
     #[test]
     fn test_shift() {
         let mut b = [0, 1, 2, 3, 4, 5, 6, 7];
@@ -513,6 +949,31 @@ mod tests {
         assert_eq!(&b, &[0, 1, 2, 3]);
     }
 
+    #[test]
+    fn test_const_stack_buffer() {
+        const BUFFER: Buffer<[u8; 16]> = Buffer::new_stack();
+        const LEN: usize = BUFFER.remaining_len();
+        const CAPACITY: usize = BUFFER.capacity();
+
+        assert_eq!(LEN, 0);
+        assert_eq!(CAPACITY, 16);
+        assert_eq!(Buffer::<[u8; 16]>::CAPACITY, 16);
+    }
+
+    #[test]
+    fn test_capacity_generic() {
+        use crate::{BufferCapacity, BufferSource};
+
+        fn capacity<T: BufferSource>(buf: &Buffer<T>) -> usize {
+            buf.capacity()
+        }
+
+        let mut bytes = [0u8; 8];
+        let buf = Buffer::new(&mut bytes[..]);
+        assert_eq!(buf.capacity(), 8);
+        assert_eq!(capacity(&buf), 8);
+    }
+
     #[test]
     fn test_stack_buffer() {
 
@@ -553,6 +1014,163 @@ mod tests {
         assert_eq!(res, Err(BufferError::NoData));
     }
 
+    #[test]
+    fn test_create_writer_compaction_policy() {
+        let mut b = [1, 2, 3, 4, 5, 6, 7, 8];
+        let mut buf = Buffer::new(&mut b);
+        buf.read_position = 2;
+        buf.write_position = 6;
+
+        buf.set_compaction_policy(CompactionPolicy::WhenFull);
+        drop(buf.create_writer());
+        assert_eq!(buf.read_position, 2);
+
+        buf.set_compaction_policy(CompactionPolicy::DeadCapacityExceeds(20));
+        drop(buf.create_writer());
+        assert_eq!(buf.read_position, 0);
+        assert_eq!(buf.data(), &[3, 4, 5, 6]);
+    }
+
+    #[test]
+    fn test_never_compact_push() {
+        let mut b = [0u8; 4];
+        let mut buf = Buffer::new(&mut b);
+        buf.set_compaction_policy(CompactionPolicy::Never);
+
+        buf.push(&[1, 2, 3, 4]).unwrap();
+        buf.skip(2).unwrap();
+        assert_eq!(buf.push(&[5]), Err(BufferError::NoCapacity));
+
+        buf.shift();
+        buf.push(&[5]).unwrap();
+        assert_eq!(buf.data(), &[3, 4, 5]);
+    }
+
+    #[test]
+    fn test_small_buffer() {
+        use crate::{BufferReader, BufferWriter, SmallBuffer};
+
+        let mut buf = SmallBuffer::<255, u8>::new_stack();
+        assert!(core::mem::size_of_val(&buf) < core::mem::size_of::<Buffer<[u8; 255]>>());
+
+        let mut writer = buf.create_writer();
+        writer[..3].copy_from_slice(&[1, 2, 3]);
+        writer.commit(3).unwrap();
+        drop(writer);
+
+        let reader = buf.create_reader();
+        assert_eq!(&reader[..], &[1, 2, 3]);
+        reader.add_bytes_read(2);
+        drop(reader);
+
+        assert_eq!(buf.remaining_len(), 1);
+        assert!(buf.has_dead_capacity());
+        buf.shift();
+        assert_eq!(buf.read_position, 0);
+        assert_eq!(buf.write_position, 1);
+    }
+
+    #[test]
+    fn test_accessors_generic_index() {
+        use crate::{BufferIndex, BufferSource, SmallBuffer};
+
+        fn state<T: BufferSource, I: BufferIndex>(buf: &Buffer<T, I>) -> (usize, bool, bool) {
+            (buf.remaining_len(), buf.has_remaining_len(), buf.has_dead_capacity())
+        }
+
+        const SMALL: SmallBuffer<16, u8> = SmallBuffer::new_stack();
+        const LEN: usize = SMALL.remaining_len();
+        assert_eq!(LEN, 0);
+
+        let mut buf = SmallBuffer::<16, u8>::new_stack();
+        assert_eq!(state(&buf), (0, false, false));
+        buf.push(&[1, 2, 3]).unwrap();
+        buf.skip(1).unwrap();
+        assert_eq!(state(&buf), (2, true, true));
+
+        let mut buf = Buffer::<[u8; 300], u16>::new_stack();
+        buf.push(&[0; 290]).unwrap();
+        buf.skip(280).unwrap();
+        assert_eq!(state(&buf), (10, true, true));
+
+        let mut buf = Buffer::<[u8; 8], u32>::new_stack();
+        buf.push(&[1]).unwrap();
+        assert_eq!(state(&buf), (1, true, false));
+    }
+
+    #[test]
+    fn test_new_indexed() {
+        let mut bytes = [0u8; 16];
+        let mut buf = Buffer::<_, u8>::new_indexed(&mut bytes[..]);
+        buf.push(&[1, 2]).unwrap();
+        assert_eq!(buf.write_position, 2u8);
+        assert_eq!(buf.data(), &[1, 2]);
+
+        let mut bytes = [0u8; 8];
+        let buf = Buffer::<&mut [u8; 8], u16>::new_indexed(&mut bytes);
+        assert_eq!(buf.capacity(), 8);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_new_indexed_too_large() {
+        let mut bytes = [0u8; 256];
+        let _ = Buffer::<_, u8>::new_indexed(&mut bytes[..]);
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_small_buffer_embedded_io() {
+        use embedded_io::{Read, Write};
+        use crate::SmallBuffer;
+
+        let mut buf = SmallBuffer::<4>::new_stack();
+        assert_eq!(buf.write(&[1, 2, 3, 4, 5]).unwrap(), 4);
+
+        let mut tgt = [0u8; 2];
+        assert_eq!(buf.read(&mut tgt).unwrap(), 2);
+        assert_eq!(buf.write(&[5, 6]).unwrap(), 2);
+        assert_eq!(buf.data(), &[3, 4, 5, 6]);
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_embedded_io_ready() {
+        use embedded_io::{Read, ReadReady, Write, WriteReady};
+
+        let mut buf = Buffer::<[u8; 2]>::new_stack();
+        assert!(! buf.read_ready().unwrap());
+        assert!(buf.write_ready().unwrap());
+
+        buf.write_all(&[1, 2]).unwrap();
+        assert!(buf.read_ready().unwrap());
+        assert!(! buf.write_ready().unwrap());
+
+        // Dead capacity is writable
+        buf.skip(1).unwrap();
+        assert!(buf.write_ready().unwrap());
+
+        buf.set_compaction_policy(CompactionPolicy::Never);
+        assert!(! buf.write_ready().unwrap());
+        assert_eq!(buf.write(&[3]), Err(embedded_io::ErrorKind::WriteZero));
+
+        let mut tgt = [0u8; 2];
+        assert_eq!(buf.read(&mut tgt), Ok(1));
+        assert_eq!(buf.read(&mut tgt), Ok(0));
+    }
+
+    #[cfg(feature = "embedded")]
+    #[test]
+    fn test_embedded_io_read_exact_eof() {
+        use embedded_io::{Read, ReadExactError};
+
+        let mut buf = Buffer::<[u8; 4]>::new_stack();
+        buf.push(&[1, 2]).unwrap();
+
+        let mut tgt = [0u8; 3];
+        assert_eq!(buf.read_exact(&mut tgt), Err(ReadExactError::UnexpectedEof));
+    }
+
     #[cfg(feature = "std")]
     #[test]
     fn test_vec_source_grow() {
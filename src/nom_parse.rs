@@ -0,0 +1,93 @@
+use nom::{Err, Needed, Parser};
+
+use crate::{BufferReader, ParseError};
+
+/// Runs [`nom`] parsers on the unread bytes of a [`BufferReader`]
+/// 
+/// ```rust
+///     use embytes_buffer::{Buffer, NomReader, ParseError, ReadWrite};
+///     use nom::number::streaming::be_u16;
+/// 
+///     let mut buf = Buffer::<[u8; 8]>::new_stack();
+///     buf.push(&[0x01]).unwrap();
+/// 
+///     let reader = buf.create_reader();
+///     let result = reader.parse_nom(be_u16::<_, nom::error::Error<_>>);
+///     assert!(matches!(result, Err(ParseError::Incomplete(Some(n))) if n.get() == 1));
+///     drop(reader);
+/// 
+///     buf.push(&[0x02]).unwrap();
+///     let reader = buf.create_reader();
+///     assert_eq!(reader.parse_nom(be_u16::<_, nom::error::Error<_>>), Ok(0x0102));
+/// ```
+pub trait NomReader: BufferReader {
+
+    /// Runs `parser` on the unread bytes and marks the bytes used by the parser as read
+    /// 
+    /// # Errors
+    /// 
+    /// [`ParseError::Incomplete`] if the parser returned [`nom::Err::Incomplete`], nothing is consumed
+    /// [`ParseError::Parse`] if the parser returned [`nom::Err::Error`] or [`nom::Err::Failure`], nothing is consumed
+    fn parse_nom<'a, P>(&'a self, mut parser: P) -> Result<P::Output, ParseError<P::Error>>
+        where P: Parser<&'a [u8]> 
+    {
+        let src = self.unread();
+        match parser.parse(src) {
+            Ok((rest, output)) => {
+                self.add_bytes_read(src.len() - rest.len());
+                Ok(output)
+            },
+            Err(Err::Incomplete(Needed::Size(n))) => Err(ParseError::Incomplete(Some(n))),
+            Err(Err::Incomplete(Needed::Unknown)) => Err(ParseError::Incomplete(None)),
+            Err(Err::Error(e) | Err::Failure(e)) => Err(ParseError::Parse(e)),
+        }
+    }
+}
+
+impl <R: BufferReader> NomReader for R {}
+
+#[cfg(test)]
+mod tests {
+    use nom::{bytes::streaming::{tag, take}, error::{Error, ErrorKind}, number::streaming::u8 as byte, Parser};
+
+    use crate::{Buffer, BufferReader, ParseError, ReadWrite};
+    use super::NomReader;
+
+    /// Parses a frame `$<len><payload>`
+    fn frame(input: &[u8]) -> nom::IResult<&[u8], &[u8]> {
+        let (input, _) = tag(&b"$"[..]).parse(input)?;
+        let (input, len) = byte(input)?;
+        take(len).parse(input)
+    }
+
+    #[test]
+    fn test_parse_frames() {
+        let mut buf = Buffer::<[u8; 16]>::new_stack();
+        buf.push(b"$\x02ab$\x03c").unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(reader.parse_nom(frame), Ok(&b"ab"[..]));
+        let result = reader.parse_nom(frame);
+        assert!(matches!(result, Err(ParseError::Incomplete(Some(n))) if n.get() == 2));
+        assert_eq!(reader.unread(), b"$\x03c");
+        drop(reader);
+
+        buf.push(b"de").unwrap();
+        let reader = buf.create_reader();
+        assert_eq!(reader.parse_nom(frame), Ok(&b"cde"[..]));
+        drop(reader);
+
+        assert!(buf.data().is_empty());
+    }
+
+    #[test]
+    fn test_parse_error_consumes_nothing() {
+        let mut buf = Buffer::<[u8; 16]>::new_stack();
+        buf.push(b"#\x01a").unwrap();
+
+        let reader = buf.create_reader();
+        let result = reader.parse_nom(frame);
+        assert!(matches!(result, Err(ParseError::Parse(Error { code: ErrorKind::Tag, .. }))));
+        assert_eq!(reader.bytes_read(), 0);
+    }
+}
@@ -0,0 +1,278 @@
+use crate::{BufferError, BufferReader, BufferWriter};
+
+/// The maximum number of decimals supported by [`NumberWriter::write_f64`]
+pub const MAX_DECIMALS: usize = 9;
+
+/// Writes numbers as ASCII to a [`BufferWriter`] without using `core::fmt`. 
+/// Every number is committed as a whole or not at all.
+pub trait NumberWriter: BufferWriter {
+
+    /// Writes `n` as decimal number and returns the number of bytes written
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoCapacity`] if the number does not fit into the writer
+    fn write_u64(&mut self, n: u64) -> Result<usize, BufferError> {
+        let mut digits = [0u8; 20];
+        let start = format_decimal(n, &mut digits);
+        write_ascii(self, &[&digits[start..]])
+    }
+
+    /// Writes `n` as decimal number with a leading `-` if it is negative and returns the number of bytes written
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoCapacity`] if the number does not fit into the writer
+    fn write_i64(&mut self, n: i64) -> Result<usize, BufferError> {
+        let mut digits = [0u8; 20];
+        let start = format_decimal(n.unsigned_abs(), &mut digits);
+        let sign: &[u8] = if n < 0 { b"-" } else { b"" };
+        write_ascii(self, &[sign, &digits[start..]])
+    }
+
+    /// Writes `n` as lower case hex number without prefix and returns the number of bytes written
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoCapacity`] if the number does not fit into the writer
+    fn write_hex(&mut self, n: u64) -> Result<usize, BufferError> {
+        const HEX: &[u8; 16] = b"0123456789abcdef";
+
+        let mut digits = [0u8; 16];
+        let mut start = digits.len();
+        let mut n = n;
+        loop {
+            start -= 1;
+            digits[start] = HEX[(n & 0xf) as usize];
+            n >>= 4;
+            if n == 0 {
+                break;
+            }
+        }
+        write_ascii(self, &[&digits[start..]])
+    }
+
+    /// Writes `v` as decimal number with `decimals` digits after the decimal point 
+    /// and returns the number of bytes written. 
+    /// `NaN` and infinite values are written as `NaN`, `inf` and `-inf`.
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoCapacity`] if the number does not fit into the writer
+    /// [`BufferError::InvalidNumber`] if `decimals > MAX_DECIMALS` or `v` does not fit into an `u64` 
+    fn write_f64(&mut self, v: f64, decimals: usize) -> Result<usize, BufferError> {
+        if v.is_nan() {
+            return write_ascii(self, &[b"NaN"]);
+        }
+        if v.is_infinite() {
+            let s: &[u8] = if v < 0.0 { b"-inf" } else { b"inf" };
+            return write_ascii(self, &[s]);
+        }
+        if decimals > MAX_DECIMALS || v.abs() >= u64::MAX as f64 {
+            return Err(BufferError::InvalidNumber);
+        }
+
+        let abs = v.abs();
+        let scale = 10u64.pow(decimals as u32);
+        let mut int = abs as u64;
+        let mut frac = ((abs - int as f64) * scale as f64 + 0.5) as u64;
+        if frac >= scale {
+            int = int.checked_add(1).ok_or(BufferError::InvalidNumber)?;
+            frac -= scale;
+        }
+
+        let mut int_digits = [0u8; 20];
+        let int_start = format_decimal(int, &mut int_digits);
+
+        let mut frac_digits = [b'0'; MAX_DECIMALS];
+        let frac_digits = &mut frac_digits[..decimals];
+        for d in frac_digits.iter_mut().rev() {
+            *d = b'0' + (frac % 10) as u8;
+            frac /= 10;
+        }
+
+        let sign: &[u8] = if v.is_sign_negative() && (int > 0 || frac_digits.iter().any(|d| *d != b'0')) { b"-" } else { b"" };
+        let point: &[u8] = if decimals > 0 { b"." } else { b"" };
+        write_ascii(self, &[sign, &int_digits[int_start..], point, frac_digits])
+    }
+}
+
+impl <W: BufferWriter> NumberWriter for W {}
+
+/// Parses ASCII numbers from a [`BufferReader`] without using `core::fmt`. 
+/// Parsing stops at the first byte that is not a digit, this byte is not consumed.
+pub trait NumberReader: BufferReader {
+
+    /// Parses an unsigned decimal number
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoData`] if the digits run to the end of the readable data, the reader is left untouched
+    /// [`BufferError::InvalidNumber`] if there is no digit or the number overflows, the reader is left untouched
+    fn parse_u64(&self) -> Result<u64, BufferError> {
+        let (n, len) = parse_digits(self.unread(), 10)?;
+        self.add_bytes_read(len);
+        Ok(n)
+    }
+
+    /// Parses a decimal number with an optional leading `+` or `-`
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoData`] if the digits run to the end of the readable data, the reader is left untouched
+    /// [`BufferError::InvalidNumber`] if there is no digit or the number overflows, the reader is left untouched
+    fn parse_i64(&self) -> Result<i64, BufferError> {
+        let src = self.unread();
+        let (negative, sign_len) = match src.first() {
+            Some(b'-') => (true, 1),
+            Some(b'+') => (false, 1),
+            _ => (false, 0),
+        };
+
+        let (n, len) = parse_digits(&src[sign_len..], 10)?;
+        let n = if negative {
+            0i64.checked_sub_unsigned(n)
+        } else {
+            i64::try_from(n).ok()
+        }.ok_or(BufferError::InvalidNumber)?;
+
+        self.add_bytes_read(sign_len + len);
+        Ok(n)
+    }
+
+    /// Parses an unsigned hex number without prefix. Upper and lower case digits are accepted.
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoData`] if the digits run to the end of the readable data, the reader is left untouched
+    /// [`BufferError::InvalidNumber`] if there is no digit or the number overflows, the reader is left untouched
+    fn parse_hex(&self) -> Result<u64, BufferError> {
+        let (n, len) = parse_digits(self.unread(), 16)?;
+        self.add_bytes_read(len);
+        Ok(n)
+    }
+}
+
+impl <R: BufferReader> NumberReader for R {}
+
+/// Writes `n` to the end of `tgt` and returns the index of the first digit
+fn format_decimal(mut n: u64, tgt: &mut [u8; 20]) -> usize {
+    let mut start = tgt.len();
+    loop {
+        start -= 1;
+        tgt[start] = b'0' + (n % 10) as u8;
+        n /= 10;
+        if n == 0 {
+            return start;
+        }
+    }
+}
+
+/// Commits all `parts` as a whole
+fn write_ascii<W: BufferWriter + ?Sized>(writer: &mut W, parts: &[&[u8]]) -> Result<usize, BufferError> {
+    let len: usize = parts.iter().map(|p| p.len()).sum();
+    if writer.remaining_capacity() < len {
+        return Err(BufferError::NoCapacity);
+    }
+
+    let mut offset = 0;
+    for part in parts {
+        writer[offset..offset + part.len()].copy_from_slice(part);
+        offset += part.len();
+    }
+    writer.commit(len)?;
+    Ok(len)
+}
+
+/// Parses the leading digits of `src` and returns the number and the count of digits
+fn parse_digits(src: &[u8], radix: u32) -> Result<(u64, usize), BufferError> {
+    let len = src.iter()
+        .position(|b| ! (*b as char).is_digit(radix))
+        .ok_or(BufferError::NoData)?;
+    if len == 0 {
+        return Err(BufferError::InvalidNumber);
+    }
+
+    src[..len].iter().try_fold(0u64, |n, b| {
+        let digit = (*b as char).to_digit(radix).expect("checked to be a digit") as u64;
+        n.checked_mul(radix as u64)?.checked_add(digit)
+    })
+    .map(|n| (n, len))
+    .ok_or(BufferError::InvalidNumber)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Buffer, BufferError, BufferReader, ReadWrite};
+    use super::{NumberReader, NumberWriter};
+
+    #[test]
+    fn test_write_integers() {
+        let mut buf = Buffer::<[u8; 64]>::new_stack();
+        let mut writer = buf.create_writer();
+        writer.write_u64(0).unwrap();
+        writer.write_u64(u64::MAX).unwrap();
+        writer.write_i64(i64::MIN).unwrap();
+        writer.write_hex(0xbeef).unwrap();
+        drop(writer);
+
+        assert_eq!(buf.data(), b"018446744073709551615-9223372036854775808beef");
+    }
+
+    #[test]
+    fn test_write_f64() {
+        let mut buf = Buffer::<[u8; 64]>::new_stack();
+        let mut writer = buf.create_writer();
+        writer.write_f64(1.25, 1).unwrap();
+        writer.write_f64(-0.999, 2).unwrap();
+        writer.write_f64(-0.001, 2).unwrap();
+        writer.write_f64(3.7, 0).unwrap();
+        writer.write_f64(f64::NAN, 3).unwrap();
+        assert_eq!(writer.write_f64(1.0, 10), Err(BufferError::InvalidNumber));
+        drop(writer);
+
+        assert_eq!(buf.data(), b"1.3-1.000.004NaN");
+    }
+
+    #[test]
+    fn test_write_no_capacity() {
+        let mut buf = Buffer::<[u8; 4]>::new_stack();
+        let mut writer = buf.create_writer();
+        writer.write_u64(12).unwrap();
+        assert_eq!(writer.write_i64(-12), Err(BufferError::NoCapacity));
+        drop(writer);
+
+        assert_eq!(buf.data(), b"12");
+    }
+
+    #[test]
+    fn test_parse() {
+        let mut buf = Buffer::<[u8; 32]>::new_stack();
+        buf.push(b"123,-45,fF;x,99").unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(reader.parse_u64(), Ok(123));
+        reader.add_bytes_read(1);
+        assert_eq!(reader.parse_i64(), Ok(-45));
+        reader.add_bytes_read(1);
+        assert_eq!(reader.parse_hex(), Ok(0xff));
+        reader.add_bytes_read(1);
+        assert_eq!(reader.parse_u64(), Err(BufferError::InvalidNumber));
+        reader.add_bytes_read(2);
+
+        // The number may continue
+        assert_eq!(reader.parse_u64(), Err(BufferError::NoData));
+        assert_eq!(reader.unread(), b"99");
+    }
+
+    #[test]
+    fn test_parse_overflow() {
+        let mut buf = Buffer::<[u8; 64]>::new_stack();
+        buf.push(b"18446744073709551616 -9223372036854775808 ").unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(reader.parse_u64(), Err(BufferError::InvalidNumber));
+        reader.add_bytes_read(21);
+        assert_eq!(reader.parse_i64(), Ok(i64::MIN));
+    }
+}
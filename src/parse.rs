@@ -0,0 +1,18 @@
+use core::num::NonZeroUsize;
+
+use thiserror::Error;
+
+/// Error of running a streaming parser on a [`crate::BufferReader`]
+#[derive(Error, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParseError<E> {
+
+    /// The parser needs more data. 
+    /// Contains the number of additional bytes if the parser knows it
+    #[error("Error parsing: more data needed")]
+    Incomplete(Option<NonZeroUsize>),
+
+    /// The parser failed
+    #[error("Error parsing: parser failed")]
+    Parse(E),
+}
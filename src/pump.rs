@@ -0,0 +1,349 @@
+use core::pin::pin;
+
+use embassy_futures::select::{select, Either};
+use embedded_io_async::{Read, Write};
+use thiserror::Error;
+
+use crate::{Buffer, BufferCapacity, BufferIndex, BufferSource};
+
+/// Error returned by [`pump`]
+#[derive(Error, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum PumpError<RE, WE> {
+
+    /// The source returned an error
+    #[error("Error reading from the source")]
+    Read(RE),
+
+    /// The sink returned an error
+    #[error("Error writing to the sink")]
+    Write(WE),
+
+    /// The sink did not accept any bytes
+    #[error("The sink accepted no bytes")]
+    WriteZero,
+
+    /// The buffer has a capacity of `0`
+    #[error("The buffer has no capacity")]
+    NoCapacity,
+}
+
+/// Moves all data from `src` to `dst` through `buffer` until `src` reaches EOF. 
+/// Returns the number of bytes written to `dst` by this call.
+/// 
+/// Reading into the free space of the buffer and writing the readable data of the buffer run concurrently. 
+/// A started write always runs to completion: while it is pending, `src` is read into the free space behind 
+/// the data of the write. A pending read is dropped if the write completes first, so `src.read` must be cancel-safe. 
+/// If the buffer is full, `src` is not read until `dst` accepted data. 
+/// After `src` reached EOF the remaining data is written and `dst` is flushed.
+/// 
+/// # Cancel safety
+/// 
+/// All state is kept in `buffer`, so dropping the future does not lose data as long as 
+/// the `read` and `write` implementations of `src` and `dst` are cancel-safe. 
+/// Calling [`pump`] again with the same buffer continues where the dropped future stopped. 
+/// The returned count starts at `0` again and does not include the bytes written before the cancellation.
+pub async fn pump<R, W, T, I>(src: &mut R, dst: &mut W, buffer: &mut Buffer<T, I>) -> Result<usize, PumpError<R::Error, W::Error>>
+where 
+    R: Read, 
+    W: Write, 
+    T: BufferSource, 
+    I: BufferIndex
+{
+    if buffer.capacity() == 0 {
+        return Err(PumpError::NoCapacity);
+    }
+
+    let mut total = 0;
+    let mut eof = false;
+
+    loop {
+        let (read_position, write_position) = (buffer.rpos(), buffer.wpos());
+        if read_position == write_position {
+            buffer.reset();
+        } else if ! buffer.has_remaining_capacity() {
+            buffer.shift();
+        }
+
+        let (read_position, write_position) = (buffer.rpos(), buffer.wpos());
+        let (head, free) = buffer.source.initialize().split_at_mut(write_position);
+        let data = &head[read_position..];
+
+        if data.is_empty() {
+            if eof {
+                dst.flush().await.map_err(PumpError::Write)?;
+                return Ok(total);
+            }
+
+            let n = src.read(free).await.map_err(PumpError::Read)?;
+            if n == 0 {
+                eof = true;
+            } else {
+                buffer.set_wpos(write_position + n);
+            }
+            continue;
+        }
+
+        // A started write runs to completion, meanwhile reads fill the free space behind its data
+        let mut read_error = None;
+        let written = {
+            let mut write = pin!(dst.write(data));
+            let mut filled = 0;
+            loop {
+                if eof || read_error.is_some() || filled == free.len() {
+                    break write.await;
+                }
+
+                match select(src.read(&mut free[filled..]), write.as_mut()).await {
+                    Either::First(Ok(0)) => eof = true,
+                    Either::First(Ok(n)) => {
+                        // Keep the read bytes even if the future is dropped while the write is pending
+                        filled += n;
+                        buffer.write_position = I::from_usize(write_position + filled);
+                    },
+                    // Reported after the pending write completed
+                    Either::First(Err(e)) => read_error = Some(e),
+                    Either::Second(written) => break written,
+                }
+            }
+        };
+
+        let n = written.map_err(PumpError::Write)?;
+        if n == 0 {
+            return Err(PumpError::WriteZero);
+        }
+        buffer.set_rpos(read_position + n);
+        total += n;
+
+        if let Some(e) = read_error {
+            return Err(PumpError::Read(e));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use core::{cell::{Cell, RefCell}, future::poll_fn, task::{Poll, Waker}};
+    use std::{rc::Rc, vec::Vec};
+
+    use embassy_futures::{block_on, join::join3, select::{select, Either}, yield_now};
+    use embedded_io_async::{ErrorKind, ErrorType, Read, Write};
+
+    use crate::Buffer;
+
+    use super::pump;
+
+    struct PipeState {
+        buffer: Buffer<[u8; 4]>,
+        closed: bool,
+        reader: Option<Waker>,
+        writer: Option<Waker>,
+    }
+
+    /// Read end of an in-memory pipe with a capacity of 4 bytes
+    struct PipeReader(Rc<RefCell<PipeState>>);
+
+    /// Write end of an in-memory pipe with a capacity of 4 bytes
+    struct PipeWriter(Rc<RefCell<PipeState>>);
+
+    fn pipe() -> (PipeWriter, PipeReader) {
+        let state = Rc::new(RefCell::new(PipeState {
+            buffer: Buffer::new_stack(),
+            closed: false,
+            reader: None,
+            writer: None,
+        }));
+        (PipeWriter(state.clone()), PipeReader(state))
+    }
+
+    impl PipeWriter {
+        fn close(&mut self) {
+            let mut state = self.0.borrow_mut();
+            state.closed = true;
+            if let Some(waker) = state.reader.take() {
+                waker.wake();
+            }
+        }
+    }
+
+    impl ErrorType for PipeReader {
+        type Error = ErrorKind;
+    }
+
+    impl ErrorType for PipeWriter {
+        type Error = ErrorKind;
+    }
+
+    impl Read for PipeReader {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            poll_fn(|cx| {
+                let mut state = self.0.borrow_mut();
+                if state.buffer.has_remaining_len() {
+                    let n = state.buffer.read_base(buf).unwrap();
+                    if let Some(waker) = state.writer.take() {
+                        waker.wake();
+                    }
+                    Poll::Ready(Ok(n))
+                } else if state.closed {
+                    Poll::Ready(Ok(0))
+                } else {
+                    state.reader = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }).await
+        }
+    }
+
+    impl Write for PipeWriter {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            poll_fn(|cx| {
+                let mut state = self.0.borrow_mut();
+                if state.buffer.ensure_remaining_capacity() {
+                    let n = state.buffer.write_base(buf).unwrap();
+                    if let Some(waker) = state.reader.take() {
+                        waker.wake();
+                    }
+                    Poll::Ready(Ok(n))
+                } else {
+                    state.writer = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }).await
+        }
+    }
+
+    async fn produce(mut writer: PipeWriter, data: &[u8]) {
+        writer.write_all(data).await.unwrap();
+        writer.close();
+    }
+
+    async fn consume(mut reader: PipeReader) -> Vec<u8> {
+        let mut received = Vec::new();
+        let mut buf = [0u8; 3];
+        loop {
+            let n = reader.read(&mut buf).await.unwrap();
+            if n == 0 {
+                return received;
+            }
+            received.extend_from_slice(&buf[..n]);
+        }
+    }
+
+    #[test]
+    fn test_pump() {
+        let data: Vec<u8> = (0..100).collect();
+        let (src_writer, mut src_reader) = pipe();
+        let (mut dst_writer, dst_reader) = pipe();
+        let mut buffer = Buffer::<[u8; 8]>::new_stack();
+
+        let pump_and_close = async {
+            let n = pump(&mut src_reader, &mut dst_writer, &mut buffer).await.unwrap();
+            dst_writer.close();
+            n
+        };
+
+        let (_, n, received) = block_on(join3(
+            produce(src_writer, &data),
+            pump_and_close,
+            consume(dst_reader),
+        ));
+
+        assert_eq!(n, 100);
+        assert_eq!(received, data);
+    }
+
+    #[test]
+    fn test_pump_cancel_and_resume() {
+        let data: Vec<u8> = (0..50).collect();
+        let (src_writer, mut src_reader) = pipe();
+        let (mut dst_writer, dst_reader) = pipe();
+        let mut buffer = Buffer::<[u8; 8]>::new_stack();
+
+        let pump_with_cancel = async {
+            let cancel = async {
+                for _ in 0..5 {
+                    yield_now().await;
+                }
+            };
+            let first = select(pump(&mut src_reader, &mut dst_writer, &mut buffer), cancel).await;
+            assert!(matches!(first, Either::Second(())));
+
+            let n = pump(&mut src_reader, &mut dst_writer, &mut buffer).await.unwrap();
+            dst_writer.close();
+            n
+        };
+
+        let (_, _, received) = block_on(join3(
+            produce(src_writer, &data),
+            pump_with_cancel,
+            consume(dst_reader),
+        ));
+
+        assert_eq!(received, data);
+    }
+
+    /// Sets the flag if a write future is dropped before it completed
+    struct CancelGuard<'a>(&'a Cell<bool>);
+
+    impl <'a> Drop for CancelGuard<'a> {
+        fn drop(&mut self) {
+            self.0.set(true);
+        }
+    }
+
+    /// A sink that is not cancel-safe: a write takes a few polls to complete
+    struct SlowSink {
+        data: Vec<u8>,
+        cancelled: Cell<bool>,
+    }
+
+    impl ErrorType for SlowSink {
+        type Error = ErrorKind;
+    }
+
+    impl Write for SlowSink {
+        async fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+            let guard = CancelGuard(&self.cancelled);
+            yield_now().await;
+            yield_now().await;
+            core::mem::forget(guard);
+
+            let n = buf.len().min(3);
+            self.data.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+    }
+
+    /// A source that takes a poll to return at most 2 bytes
+    struct SlowSource<'a>(&'a [u8]);
+
+    impl <'a> ErrorType for SlowSource<'a> {
+        type Error = ErrorKind;
+    }
+
+    impl <'a> Read for SlowSource<'a> {
+        async fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+            yield_now().await;
+            let n = buf.len().min(self.0.len()).min(2);
+            buf[..n].copy_from_slice(&self.0[..n]);
+            self.0 = &self.0[n..];
+            Ok(n)
+        }
+    }
+
+    #[test]
+    fn test_pump_completes_started_writes() {
+        let data: Vec<u8> = (0..100).collect();
+        let mut src = SlowSource(&data);
+        let mut dst = SlowSink { data: Vec::new(), cancelled: Cell::new(false) };
+        let mut buffer = Buffer::<[u8; 8]>::new_stack();
+
+        let n = block_on(pump(&mut src, &mut dst, &mut buffer)).unwrap();
+
+        assert!(!dst.cancelled.get());
+        assert_eq!(n, 100);
+        assert_eq!(dst.data, data);
+    }
+}
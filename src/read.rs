@@ -1,24 +1,199 @@
 use core::{cell::Cell, ops::Deref};
 
-use crate::Buffer;
+use crate::{find_any, find_byte, find_subslice, Buffer, BufferError, BufferIndex, BufferSource};
 
 /// A Reader to read from a buffer like from a byte slice
 pub trait BufferReader: Deref<Target = [u8]> {
 
     /// Tells the reader that `n` bytes were read
     fn add_bytes_read(&self, n: usize);
+
+    /// Returns the number of bytes marked as read with [`BufferReader::add_bytes_read`]
+    fn bytes_read(&self) -> usize;
+
+    /// Returns the bytes that are not marked as read yet
+    fn unread(&self) -> &[u8] {
+        &self[self.bytes_read()..]
+    }
+
+    /// Returns the index of the first `needle` in the unread bytes, see [`find_byte`]
+    fn find_byte(&self, needle: u8) -> Option<usize> {
+        find_byte(self.unread(), needle)
+    }
+
+    /// Returns the index of the first unread byte that is one of `needles`, see [`find_any`]
+    fn find_any(&self, needles: &[u8]) -> Option<usize> {
+        find_any(self.unread(), needles)
+    }
+
+    /// Returns the index of the first occurrence of `needle` in the unread bytes, see [`find_subslice`]
+    fn find_subslice(&self, needle: &[u8]) -> Option<usize> {
+        find_subslice(self.unread(), needle)
+    }
+
+    /// Marks the bytes before the first occurrence of `pattern` as read and returns `true` if it was found. 
+    /// Use this to resync to a magic sequence. 
+    /// If `pattern` is not found all bytes are marked as read except a possible partial match at the end.
+    fn skip_until(&self, pattern: &[u8]) -> bool {
+        let src = self.unread();
+        match find_subslice(src, pattern) {
+            Some(n) => {
+                self.add_bytes_read(n);
+                true
+            },
+            None => {
+                let keep = (1..pattern.len().min(src.len() + 1)).rev()
+                    .find(|n| src.ends_with(&pattern[..*n]))
+                    .unwrap_or(0);
+                self.add_bytes_read(src.len() - keep);
+                false
+            }
+        }
+    }
+
+    /// Reads the unread bytes until `delim` and consumes the delimiter. 
+    /// The returned slice does not contain the delimiter.
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoData`] if there is no delimiter, the reader is left untouched
+    fn read_until(&self, delim: u8) -> Result<&[u8], BufferError> {
+        self.read_until_max(delim, usize::MAX)
+    }
+
+    /// Like [`BufferReader::read_until`] but returns at most `max_len` bytes
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoData`] if there is no delimiter yet, the reader is left untouched
+    /// [`BufferError::MaxLengthExceeded`] if there is no delimiter within `max_len` bytes, the reader is left untouched
+    fn read_until_max(&self, delim: u8, max_len: usize) -> Result<&[u8], BufferError> {
+        self.read_until_seq_max(&[delim], max_len)
+    }
+
+    /// Reads the unread bytes until the delimiter sequence `delim` and consumes the delimiter. 
+    /// The returned slice does not contain the delimiter.
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoData`] if there is no delimiter, the reader is left untouched
+    fn read_until_seq(&self, delim: &[u8]) -> Result<&[u8], BufferError> {
+        self.read_until_seq_max(delim, usize::MAX)
+    }
+
+    /// Like [`BufferReader::read_until_seq`] but returns at most `max_len` bytes
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoData`] if there is no delimiter yet, the reader is left untouched
+    /// [`BufferError::MaxLengthExceeded`] if there is no delimiter within `max_len` bytes, the reader is left untouched
+    fn read_until_seq_max(&self, delim: &[u8], max_len: usize) -> Result<&[u8], BufferError> {
+        let src = self.unread();
+        if delim.is_empty() {
+            return Ok(&[]);
+        }
+
+        let search_len = src.len().min(max_len.saturating_add(delim.len()));
+        match find_subslice(&src[..search_len], delim) {
+            Some(n) => {
+                self.add_bytes_read(n + delim.len());
+                Ok(&src[..n])
+            },
+            None if src.len() > max_len.saturating_add(delim.len() - 1) => Err(BufferError::MaxLengthExceeded),
+            None => Err(BufferError::NoData),
+        }
+    }
+
+    /// Reads a line terminated by `\n`, `\r\n` or `\r` and consumes the terminator. 
+    /// The returned slice does not contain the terminator.
+    /// 
+    /// A `\r` at the end of the unread data may be the start of a `\r\n`, 
+    /// so the line is returned after the next byte was written.
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoData`] if there is no complete line, the reader is left untouched
+    /// [`BufferError::MaxLengthExceeded`] if there is no line end within `max_len` bytes, the reader is left untouched
+    fn read_line_max(&self, max_len: usize) -> Result<&[u8], BufferError> {
+        let src = self.unread();
+        let search_len = src.len().min(max_len.saturating_add(1));
+
+        match find_any(&src[..search_len], b"\n\r") {
+            Some(n) if src[n] == b'\n' => {
+                self.add_bytes_read(n + 1);
+                Ok(&src[..n])
+            },
+            Some(n) => match src.get(n + 1) {
+                Some(b'\n') => {
+                    self.add_bytes_read(n + 2);
+                    Ok(&src[..n])
+                },
+                Some(_) => {
+                    self.add_bytes_read(n + 1);
+                    Ok(&src[..n])
+                },
+                None => Err(BufferError::NoData),
+            },
+            None if src.len() > max_len => Err(BufferError::MaxLengthExceeded),
+            None => Err(BufferError::NoData),
+        }
+    }
+
+    /// Returns an iterator over the complete lines, see [`BufferReader::read_line_max`]
+    fn lines(&self) -> Lines<'_, Self> where Self: Sized {
+        Lines { reader: self, max_len: usize::MAX, done: false }
+    }
+}
+
+/// An iterator over the lines of a [`BufferReader`] created by [`BufferReader::lines`]. 
+/// Every line is consumed when it is returned. 
+/// The iterator ends if there is no complete line left or returns [`BufferError::MaxLengthExceeded`] once.
+pub struct Lines<'a, R: BufferReader> {
+    reader: &'a R,
+    max_len: usize,
+    done: bool,
+}
+
+impl <'a, R: BufferReader> Lines<'a, R> {
+
+    /// Sets the maximum length of a line without the terminator
+    pub fn max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+}
+
+impl <'a, R: BufferReader> Iterator for Lines<'a, R> {
+    type Item = Result<&'a [u8], BufferError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.reader.read_line_max(self.max_len) {
+            Ok(line) => Some(Ok(line)),
+            Err(e) => {
+                self.done = true;
+                match e {
+                    BufferError::MaxLengthExceeded => Some(Err(e)),
+                    _ => None,
+                }
+            },
+        }
+    }
 }
 
 /// An implementation of [`BufferReader`] for [`Buffer`]
-pub struct Reader <'a, T: AsMut<[u8]> + AsRef<[u8]>> {
-    buffer: &'a mut Buffer<T>,
+pub struct Reader <'a, T: BufferSource, I: BufferIndex = usize> {
+    buffer: &'a mut Buffer<T, I>,
     bytes_read: Cell<usize>,
     max_bytes: Option<usize>
 }
 
-impl <'a, T: AsMut<[u8]> + AsRef<[u8]>> Reader<'a, T> {
+impl <'a, T: BufferSource, I: BufferIndex> Reader<'a, T, I> {
 
-    pub(crate) fn new(buf: &'a mut Buffer<T>) -> Self {
+    pub(crate) fn new(buf: &'a mut Buffer<T, I>) -> Self {
         Self {
             buffer: buf,
             bytes_read: Cell::new(0),
@@ -26,29 +201,28 @@ impl <'a, T: AsMut<[u8]> + AsRef<[u8]>> Reader<'a, T> {
         }
     }
 
-    pub(crate) fn new_with_max(buf: &'a mut Buffer<T>, max_bytes: usize) -> Self {
+    pub(crate) fn new_with_max(buf: &'a mut Buffer<T, I>, max_bytes: usize) -> Self {
         Self {
             buffer: buf,
             bytes_read: Cell::new(0),
             max_bytes: Some(max_bytes)
         }
     }
-
-    #[cfg(test)]
-    pub(crate) fn get_bytes_read(&self) -> usize {
-        self.bytes_read.get()
-    }
 }
 
-impl <'a, T: AsMut<[u8]> + AsRef<[u8]>> BufferReader for Reader<'a, T> {
+impl <'a, T: BufferSource, I: BufferIndex> BufferReader for Reader<'a, T, I> {
     fn add_bytes_read(&self, n: usize) {
         self.bytes_read.set(
             self.bytes_read.get() + n
         );
     }
+
+    fn bytes_read(&self) -> usize {
+        self.bytes_read.get()
+    }
 }
 
-impl <'a, T: AsMut<[u8]> + AsRef<[u8]>> Drop for Reader<'a, T> {
+impl <'a, T: BufferSource, I: BufferIndex> Drop for Reader<'a, T, I> {
     fn drop(&mut self) {
         let bytes_read = self.bytes_read.get();
         self.buffer.skip(bytes_read)
@@ -56,7 +230,7 @@ impl <'a, T: AsMut<[u8]> + AsRef<[u8]>> Drop for Reader<'a, T> {
     }
 }
 
-impl <'a, T: AsMut<[u8]> + AsRef<[u8]>> Deref for Reader<'a, T> {
+impl <'a, T: BufferSource, I: BufferIndex> Deref for Reader<'a, T, I> {
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
@@ -70,7 +244,7 @@ impl <'a, T: AsMut<[u8]> + AsRef<[u8]>> Deref for Reader<'a, T> {
 
 #[cfg(test)]
 mod tests {
-    use crate::{Buffer, BufferReader};
+    use crate::{Buffer, BufferError, BufferReader, ReadWrite};
     use super::Reader;
 
 
@@ -86,11 +260,68 @@ mod tests {
         assert_eq!(&reader[..], &[1, 2, 3, 4]);
 
         reader.add_bytes_read(3);
-        assert_eq!(reader.get_bytes_read(), 3);
+        assert_eq!(reader.bytes_read(), 3);
         drop(reader);
 
         assert_eq!(buf.read_position, 3);
         assert_eq!(buf.write_position, 4);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_read_until() {
+        let mut buf = Buffer::<[u8; 16]>::new_stack();
+        buf.push(b"ab,cd,ef").unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(reader.read_until(b','), Ok(&b"ab"[..]));
+        assert_eq!(reader.read_until(b','), Ok(&b"cd"[..]));
+        assert_eq!(reader.read_until(b','), Err(BufferError::NoData));
+        assert_eq!(reader.unread(), b"ef");
+        drop(reader);
+
+        assert_eq!(buf.data(), b"ef");
+    }
+
+    #[test]
+    fn test_read_until_seq_max() {
+        let mut buf = Buffer::<[u8; 16]>::new_stack();
+        buf.push(b"abc--de-").unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(reader.read_until_seq_max(b"--", 2), Err(BufferError::MaxLengthExceeded));
+        assert_eq!(reader.read_until_seq_max(b"--", 3), Ok(&b"abc"[..]));
+        assert_eq!(reader.read_until_seq_max(b"--", 2), Err(BufferError::NoData));
+        assert_eq!(reader.read_until_seq(b"--"), Err(BufferError::NoData));
+        drop(reader);
+
+        assert_eq!(buf.data(), b"de-");
+    }
+
+    #[test]
+    fn test_lines() {
+        let mut buf = Buffer::<[u8; 32]>::new_stack();
+        buf.push(b"a\nbc\r\nd\re\r").unwrap();
+
+        let reader = buf.create_reader();
+        let mut lines = reader.lines();
+        assert_eq!(lines.next(), Some(Ok(&b"a"[..])));
+        assert_eq!(lines.next(), Some(Ok(&b"bc"[..])));
+        assert_eq!(lines.next(), Some(Ok(&b"d"[..])));
+        assert_eq!(lines.next(), None);
+        drop(reader);
+
+        // The trailing \r may be followed by \n
+        assert_eq!(buf.data(), b"e\r");
+        buf.push(b"\nfghij").unwrap();
+
+        let reader = buf.create_reader();
+        let mut lines = reader.lines().max_len(3);
+        assert_eq!(lines.next(), Some(Ok(&b"e"[..])));
+        assert_eq!(lines.next(), Some(Err(BufferError::MaxLengthExceeded)));
+        assert_eq!(lines.next(), None);
+        drop(reader);
+
+        assert_eq!(buf.data(), b"fghij");
+    }
+
+}
@@ -0,0 +1,222 @@
+use core::mem::size_of;
+
+use crate::{Buffer, BufferIndex, BufferSource};
+
+const WORD: usize = size_of::<usize>();
+const LO: usize = usize::MAX / 0xff;
+const HI: usize = LO * 0x80;
+
+/// Returns a word with every byte set to `b`
+const fn splat(b: u8) -> usize {
+    LO * b as usize
+}
+
+/// Returns `true` if one of the bytes of `word` is `0`
+const fn has_zero(word: usize) -> bool {
+    word.wrapping_sub(LO) & !word & HI != 0
+}
+
+/// Returns the index of the first `needle` in `haystack`.
+/// Compares a word at a time (SWAR).
+pub fn find_byte(haystack: &[u8], needle: u8) -> Option<usize> {
+    let pattern = splat(needle);
+    let mut chunks = haystack.chunks_exact(WORD);
+    for (i, chunk) in chunks.by_ref().enumerate() {
+        let word = usize::from_ne_bytes(chunk.try_into().expect("chunk has the size of a word"));
+        if has_zero(word ^ pattern) {
+            return chunk.iter().position(|b| *b == needle).map(|n| i * WORD + n);
+        }
+    }
+
+    let rest = chunks.remainder();
+    rest.iter().position(|b| *b == needle).map(|n| haystack.len() - rest.len() + n)
+}
+
+/// Returns the index of the first byte in `haystack` that is one of `needles`.
+/// Up to three needles are compared a word at a time (SWAR), more needles use a lookup table.
+pub fn find_any(haystack: &[u8], needles: &[u8]) -> Option<usize> {
+    match needles {
+        [] => None,
+        [a] => find_byte(haystack, *a),
+        [a, b] | [a, b, _] => {
+            let c = needles.get(2).copied().unwrap_or(*b);
+            let (a, b, c) = (splat(*a), splat(*b), splat(c));
+            let mut chunks = haystack.chunks_exact(WORD);
+            for (i, chunk) in chunks.by_ref().enumerate() {
+                let word = usize::from_ne_bytes(chunk.try_into().expect("chunk has the size of a word"));
+                if has_zero(word ^ a) || has_zero(word ^ b) || has_zero(word ^ c) {
+                    return chunk.iter().position(|b| needles.contains(b)).map(|n| i * WORD + n);
+                }
+            }
+
+            let rest = chunks.remainder();
+            rest.iter().position(|b| needles.contains(b)).map(|n| haystack.len() - rest.len() + n)
+        },
+        _ => {
+            let mut table = [false; 256];
+            for b in needles {
+                table[*b as usize] = true;
+            }
+            haystack.iter().position(|b| table[*b as usize])
+        }
+    }
+}
+
+/// Returns the index of the first occurrence of `needle` in `haystack`.
+/// Candidates are located with [`find_byte`] on the first byte of `needle`.
+/// An empty `needle` is found at index `0`.
+pub fn find_subslice(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    let Some((first, rest)) = needle.split_first() else { return Some(0) };
+
+    let mut offset = 0;
+    while haystack.len() - offset >= needle.len() {
+        let candidate = offset + find_byte(&haystack[offset..=haystack.len() - needle.len()], *first)?;
+        if haystack[candidate + 1..candidate + needle.len()] == *rest {
+            return Some(candidate);
+        }
+        offset = candidate + 1;
+    }
+    None
+}
+
+/// Searches `needle` in data that grows between searches, like the readable data of a [`Buffer`].
+/// Remembers how far the data was scanned, so repeated searches do not scan the same bytes again.
+///
+/// The scan offset is relative to the start of the data.
+/// If bytes are removed from the start of the data, tell the finder with [`StreamFinder::consume`].
+///
+/// ```rust
+///     use embytes_buffer::StreamFinder;
+///
+///     let mut finder = StreamFinder::new(b"\r\n");
+///     assert_eq!(finder.find(b"abc\r"), None);
+///     assert_eq!(finder.scanned(), 3);
+///     assert_eq!(finder.find(b"abc\r\n"), Some(3));
+/// ```
+pub struct StreamFinder<'n> {
+    needle: &'n [u8],
+    scanned: usize,
+}
+
+impl <'n> StreamFinder<'n> {
+
+    /// Creates a finder for `needle`
+    pub const fn new(needle: &'n [u8]) -> Self {
+        Self { needle, scanned: 0 }
+    }
+
+    /// Returns the number of bytes at the start of the data that do not need to be scanned again
+    pub fn scanned(&self) -> usize {
+        self.scanned
+    }
+
+    /// Returns the index of the first `needle` in `data`.
+    /// Only the bytes that were not scanned yet and a possible partial match before them are scanned.
+    pub fn find(&mut self, data: &[u8]) -> Option<usize> {
+        let start = self.scanned.min(data.len());
+        match find_subslice(&data[start..], self.needle) {
+            Some(n) => {
+                self.scanned = start + n;
+                Some(start + n)
+            },
+            None => {
+                // The end of the data can be the start of a match
+                self.scanned = (data.len() + 1).saturating_sub(self.needle.len()).max(start);
+                None
+            }
+        }
+    }
+
+    /// Tells the finder that `n` bytes were removed from the start of the data
+    pub fn consume(&mut self, n: usize) {
+        self.scanned = self.scanned.saturating_sub(n);
+    }
+
+    /// Scans the data from the start on the next search
+    pub fn reset(&mut self) {
+        self.scanned = 0;
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> Buffer<T, I> {
+
+    /// Returns the index of the first `needle` in the readable data, see [`find_byte`]
+    pub fn find_byte(&self, needle: u8) -> Option<usize> {
+        find_byte(self.data(), needle)
+    }
+
+    /// Returns the index of the first byte in the readable data that is one of `needles`, see [`find_any`]
+    pub fn find_any(&self, needles: &[u8]) -> Option<usize> {
+        find_any(self.data(), needles)
+    }
+
+    /// Returns the index of the first occurrence of `needle` in the readable data, see [`find_subslice`]
+    pub fn find_subslice(&self, needle: &[u8]) -> Option<usize> {
+        find_subslice(self.data(), needle)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Buffer, BufferReader, ReadWrite};
+    use super::{find_any, find_byte, find_subslice, StreamFinder};
+
+    #[test]
+    fn test_find_byte() {
+        let data = b"0123456789abcdefghij";
+        for (i, b) in data.iter().enumerate() {
+            assert_eq!(find_byte(data, *b), Some(i));
+        }
+        assert_eq!(find_byte(data, b'z'), None);
+        assert_eq!(find_byte(&[0x80, 0x81, 0x01], 0x01), Some(2));
+        assert_eq!(find_byte(&[], 0), None);
+    }
+
+    #[test]
+    fn test_find_any() {
+        let data = b"abcdefghijklmnopq,rs\nt";
+        assert_eq!(find_any(data, b",\n"), Some(17));
+        assert_eq!(find_any(data, b"\nxt"), Some(20));
+        assert_eq!(find_any(data, b"zyxwq"), Some(16));
+        assert_eq!(find_any(data, b""), None);
+    }
+
+    #[test]
+    fn test_find_subslice() {
+        let data = b"aaabaaabaaac";
+        assert_eq!(find_subslice(data, b"aaac"), Some(8));
+        assert_eq!(find_subslice(data, b"ba"), Some(3));
+        assert_eq!(find_subslice(data, b""), Some(0));
+        assert_eq!(find_subslice(data, b"aaaa"), None);
+        assert_eq!(find_subslice(b"ab", b"abc"), None);
+    }
+
+    #[test]
+    fn test_stream_finder() {
+        let mut finder = StreamFinder::new(b"$$");
+        assert_eq!(finder.find(b"abcdef$"), None);
+        assert_eq!(finder.scanned(), 6);
+        assert_eq!(finder.find(b"abcdef$$"), Some(6));
+
+        finder.consume(8);
+        assert_eq!(finder.scanned(), 0);
+    }
+
+    #[test]
+    fn test_buffer_and_reader_search() {
+        let mut buf = Buffer::<[u8; 32]>::new_stack();
+        buf.push(b"xx\xAA\x55frame").unwrap();
+        assert_eq!(buf.find_byte(b'f'), Some(4));
+        assert_eq!(buf.find_any(b"mr"), Some(5));
+        assert_eq!(buf.find_subslice(b"\xAA\x55"), Some(2));
+
+        let reader = buf.create_reader();
+        assert!(reader.skip_until(b"\xAA\x55"));
+        assert_eq!(reader.find_subslice(b"\xAA\x55"), Some(0));
+        assert!(! reader.skip_until(b"mex"));
+        drop(reader);
+
+        // The possible start of the pattern is kept
+        assert_eq!(buf.data(), b"me");
+    }
+}
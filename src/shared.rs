@@ -0,0 +1,409 @@
+use std::{io::{self, BufRead, ErrorKind, Read, Write}, sync::{Arc, Condvar, Mutex, MutexGuard}, time::{Duration, Instant}};
+
+use crate::{Buffer, BufferCapacity, BufferIndex, BufferSource};
+
+struct State<T: BufferSource, I: BufferIndex = usize> {
+    buffer: Buffer<T, I>,
+    producers: usize,
+    consumers: usize,
+    write_closed: bool,
+    read_closed: bool,
+}
+
+struct Shared<T: BufferSource, I: BufferIndex = usize> {
+    state: Mutex<State<T, I>>,
+    readable: Condvar,
+    writable: Condvar,
+}
+
+/// A [`Buffer`] that is shared between threads as an in-process pipe. 
+/// 
+/// Data is written with a [`Producer`] and read with a [`Consumer`]. Both block until 
+/// data or capacity is available instead of returning [`ErrorKind::WouldBlock`].
+/// 
+/// If all producers are dropped or [`Producer::close`] is called, consumers read the remaining data and then EOF. 
+/// If all consumers are dropped, producers fail with [`ErrorKind::BrokenPipe`].
+/// 
+/// # Example
+/// 
+/// ```rust
+///     use std::io::{Read, Write};
+///     use embytes_buffer::{Buffer, SharedBuffer};
+/// 
+///     let shared = SharedBuffer::new(Buffer::new_heap(16));
+///     let mut producer = shared.producer();
+///     let mut consumer = shared.consumer();
+/// 
+///     let handle = std::thread::spawn(move || {
+///         producer.write_all(&[7; 100]).unwrap();
+///     });
+/// 
+///     let mut received = Vec::new();
+///     consumer.read_to_end(&mut received).unwrap();
+///     handle.join().unwrap();
+///     assert_eq!(received, vec![7; 100]);
+/// ```
+pub struct SharedBuffer<T: BufferSource, I: BufferIndex = usize> {
+    inner: Arc<Shared<T, I>>,
+}
+
+impl <T: BufferSource, I: BufferIndex> Clone for SharedBuffer<T, I> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone() }
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> SharedBuffer<T, I> {
+
+    /// Creates a new [`SharedBuffer`] from a [`Buffer`]
+    pub fn new(buffer: Buffer<T, I>) -> Self {
+        Self {
+            inner: Arc::new(Shared {
+                state: Mutex::new(State {
+                    buffer,
+                    producers: 0,
+                    consumers: 0,
+                    write_closed: false,
+                    read_closed: false,
+                }),
+                readable: Condvar::new(),
+                writable: Condvar::new(),
+            }),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State<T, I>> {
+        self.inner.state.lock()
+            .unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Creates a new handle to write to the buffer
+    pub fn producer(&self) -> Producer<T, I> {
+        self.lock().producers += 1;
+        Producer { shared: self.clone(), timeout: None }
+    }
+
+    /// Creates a new handle to read from the buffer
+    pub fn consumer(&self) -> Consumer<T, I> {
+        let capacity = {
+            let mut state = self.lock();
+            state.consumers += 1;
+            state.buffer.capacity()
+        };
+        Consumer { shared: self.clone(), local: Buffer::new_heap(capacity), timeout: None }
+    }
+
+    /// Closes the buffer for both sides. 
+    /// Consumers read the remaining data and then EOF, producers fail with [`ErrorKind::BrokenPipe`].
+    pub fn close(&self) {
+        let mut state = self.lock();
+        state.write_closed = true;
+        state.read_closed = true;
+        self.inner.readable.notify_all();
+        self.inner.writable.notify_all();
+    }
+
+    /// Returns the number of readable bytes
+    pub fn len(&self) -> usize {
+        self.lock().buffer.remaining_len()
+    }
+
+    /// Returns `true` if there are no readable bytes
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns `true` if no more data can be written to the buffer
+    pub fn is_write_closed(&self) -> bool {
+        self.lock().write_closed
+    }
+
+    /// Blocks until data is available and reads it into `buf`. 
+    /// Returns `0` if the buffer is closed for writing and all data was read.
+    fn read_blocking(&self, buf: &mut [u8], timeout: Option<Duration>) -> io::Result<usize> {
+        let state = self.lock();
+        let mut state = self.wait_while(state, &self.inner.readable, timeout, |state| {
+            ! state.write_closed && ! state.buffer.has_remaining_len()
+        })?;
+
+        if ! state.buffer.has_remaining_len() {
+            return Ok(0);
+        }
+
+        let n = state.buffer.read_base(buf)
+            .map_err(|_| io::Error::from(ErrorKind::WouldBlock))?;
+        self.inner.writable.notify_all();
+        Ok(n)
+    }
+
+    /// Waits on `condvar` while `condition` is `true`. 
+    /// Returns [`ErrorKind::TimedOut`] if the condition is still `true` after `timeout`.
+    fn wait_while<'a>(
+        &self, 
+        mut state: MutexGuard<'a, State<T, I>>, 
+        condvar: &Condvar, 
+        timeout: Option<Duration>,
+        mut condition: impl FnMut(&mut State<T, I>) -> bool
+    ) -> io::Result<MutexGuard<'a, State<T, I>>> {
+        let deadline = timeout.map(|t| Instant::now() + t);
+
+        while condition(&mut state) {
+            state = match deadline {
+                None => condvar.wait(state).unwrap_or_else(|e| e.into_inner()),
+                Some(deadline) => {
+                    let remaining = deadline.saturating_duration_since(Instant::now());
+                    if remaining.is_zero() {
+                        return Err(ErrorKind::TimedOut.into());
+                    }
+                    condvar.wait_timeout(state, remaining)
+                        .unwrap_or_else(|e| e.into_inner()).0
+                }
+            };
+        }
+
+        Ok(state)
+    }
+}
+
+/// Handle to write to a [`SharedBuffer`]
+pub struct Producer<T: BufferSource, I: BufferIndex = usize> {
+    shared: SharedBuffer<T, I>,
+    timeout: Option<Duration>,
+}
+
+impl <T: BufferSource, I: BufferIndex> Producer<T, I> {
+
+    /// Sets the timeout for blocking writes. `None` blocks until capacity is available.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Closes the buffer for writing. Consumers read the remaining data and then EOF.
+    pub fn close(&self) {
+        let mut state = self.shared.lock();
+        state.write_closed = true;
+        self.shared.inner.readable.notify_all();
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> Write for Producer<T, I> {
+
+    /// Writes to the buffer and blocks until capacity is available
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        let state = self.shared.lock();
+        let mut state = self.shared.wait_while(state, &self.shared.inner.writable, self.timeout, |state| {
+            ! state.read_closed && ! state.write_closed && ! state.buffer.ensure_remaining_capacity()
+        })?;
+
+        if state.read_closed || state.write_closed {
+            return Err(ErrorKind::BrokenPipe.into());
+        }
+
+        let n = state.buffer.write_base(buf)
+            .map_err(|_| io::Error::from(ErrorKind::WouldBlock))?;
+        self.shared.inner.readable.notify_all();
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> Drop for Producer<T, I> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.producers -= 1;
+        if state.producers == 0 {
+            state.write_closed = true;
+            self.shared.inner.readable.notify_all();
+        }
+    }
+}
+
+/// Handle to read from a [`SharedBuffer`]
+/// 
+/// For [`BufRead`] the consumer moves data from the shared buffer into a local buffer 
+/// with the same capacity.
+pub struct Consumer<T: BufferSource, I: BufferIndex = usize> {
+    shared: SharedBuffer<T, I>,
+    local: Buffer<Vec<u8>>,
+    timeout: Option<Duration>,
+}
+
+impl <T: BufferSource, I: BufferIndex> Consumer<T, I> {
+
+    /// Sets the timeout for blocking reads. `None` blocks until data is available.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> Read for Consumer<T, I> {
+
+    /// Reads from the buffer and blocks until data is available
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        if self.local.has_remaining_len() {
+            return self.local.read(buf);
+        }
+
+        self.shared.read_blocking(buf, self.timeout)
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> BufRead for Consumer<T, I> {
+
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if ! self.local.has_remaining_len() {
+            self.local.reset();
+            let capacity = self.local.capacity();
+            let n = self.shared.read_blocking(&mut self.local.source[..capacity], self.timeout)?;
+            self.local.set_wpos(n);
+        }
+
+        Ok(self.local.data())
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.local.skip(amt.min(self.local.remaining_len()))
+            .expect("Consumer: cannot consume more than the buffered data");
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> Drop for Consumer<T, I> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.consumers -= 1;
+        if state.consumers == 0 {
+            state.read_closed = true;
+            self.shared.inner.writable.notify_all();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::{BufRead, ErrorKind, Read, Write}, thread, time::Duration};
+
+    use crate::{Buffer, SmallBuffer, UninitArray};
+
+    use super::SharedBuffer;
+
+    #[test]
+    fn test_producer_consumer_threads() {
+        let shared = SharedBuffer::new(Buffer::new_heap(8));
+        let mut producer = shared.producer();
+        let mut consumer = shared.consumer();
+
+        let data: Vec<u8> = (0..=255).cycle().take(10_000).collect();
+        let expected = data.clone();
+
+        let handle = thread::spawn(move || {
+            producer.write_all(&data).unwrap();
+        });
+
+        let mut received = Vec::new();
+        consumer.read_to_end(&mut received).unwrap();
+        handle.join().unwrap();
+
+        assert_eq!(received, expected);
+    }
+
+    #[test]
+    fn test_read_timeout() {
+        let shared = SharedBuffer::new(Buffer::new_heap(8));
+        let _producer = shared.producer();
+        let mut consumer = shared.consumer();
+        consumer.set_timeout(Some(Duration::from_millis(10)));
+
+        let mut buf = [0u8; 4];
+        let err = consumer.read(&mut buf).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn test_write_timeout() {
+        let shared = SharedBuffer::new(Buffer::new_heap(2));
+        let mut producer = shared.producer();
+        let _consumer = shared.consumer();
+        producer.set_timeout(Some(Duration::from_millis(10)));
+
+        producer.write_all(&[1, 2]).unwrap();
+        let err = producer.write(&[3]).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn test_close_semantics() {
+        let shared = SharedBuffer::new(Buffer::new_heap(8));
+        let mut producer = shared.producer();
+        let mut consumer = shared.consumer();
+
+        producer.write_all(&[1, 2, 3]).unwrap();
+        drop(producer);
+        assert!(shared.is_write_closed());
+
+        let mut buf = [0u8; 8];
+        assert_eq!(consumer.read(&mut buf).unwrap(), 3);
+        assert_eq!(consumer.read(&mut buf).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_consumer_drop_breaks_pipe() {
+        let shared = SharedBuffer::new(Buffer::new_heap(8));
+        let mut producer = shared.producer();
+        let consumer = shared.consumer();
+
+        producer.write_all(&[1]).unwrap();
+        drop(consumer);
+        assert!(!shared.is_write_closed());
+        assert_eq!(producer.write(&[2]).unwrap_err().kind(), ErrorKind::BrokenPipe);
+    }
+
+    #[test]
+    fn test_small_and_uninit_buffer() {
+        let shared = SharedBuffer::new(SmallBuffer::<8, u8>::new_stack());
+        let mut producer = shared.producer();
+        let mut consumer = shared.consumer();
+        producer.write_all(&[1, 2]).unwrap();
+        drop(producer);
+
+        let mut received = Vec::new();
+        consumer.read_to_end(&mut received).unwrap();
+        assert_eq!(received, [1, 2]);
+
+        let shared = SharedBuffer::new(Buffer::<UninitArray<8>>::new_uninit());
+        let mut producer = shared.producer();
+        let mut consumer = shared.consumer();
+        producer.write_all(&[3]).unwrap();
+        drop(producer);
+
+        let mut received = Vec::new();
+        consumer.read_to_end(&mut received).unwrap();
+        assert_eq!(received, [3]);
+    }
+
+    #[test]
+    fn test_buf_read_lines() {
+        let shared = SharedBuffer::new(Buffer::new_heap(8));
+        let mut producer = shared.producer();
+        let consumer = shared.consumer();
+
+        let handle = thread::spawn(move || {
+            producer.write_all(b"first line\nsecond\n").unwrap();
+        });
+
+        let lines: Vec<String> = consumer.lines().map(|l| l.unwrap()).collect();
+        handle.join().unwrap();
+
+        assert_eq!(lines, vec!["first line", "second"]);
+    }
+}
@@ -0,0 +1,47 @@
+/// The storage of a [`crate::Buffer`]
+///
+/// Implemented for every type that is [`AsMut<[u8]>`] and [`AsRef<[u8]>`].
+/// Sources that are not completely initialized like [`crate::UninitArray`] implement it directly:
+/// their size can exceed the initialized bytes, which grow as bytes are written.
+///
+/// The bytes before the write position of a buffer are always initialized.
+pub trait BufferSource {
+
+    /// Returns the number of bytes that can be stored, which is the capacity of the buffer
+    fn size(&self) -> usize;
+
+    /// Returns the initialized bytes
+    fn initialized(&self) -> &[u8];
+
+    /// Returns the initialized bytes as mutable slice
+    fn initialized_mut(&mut self) -> &mut [u8];
+
+    /// Writes `data` at `offset`. Bytes between the initialized bytes and `offset` are initialized with `0`.
+    ///
+    /// # Panics
+    ///
+    /// If `offset + data.len()` exceeds [`BufferSource::size`]
+    fn write_at(&mut self, offset: usize, data: &[u8]) {
+        self.initialized_mut()[offset..offset + data.len()].copy_from_slice(data);
+    }
+
+    /// Initializes all bytes up to [`BufferSource::size`] and returns them.
+    /// Used where the free space is handed out as a slice, like by [`crate::Write`].
+    fn initialize(&mut self) -> &mut [u8] {
+        self.initialized_mut()
+    }
+}
+
+impl <T: AsMut<[u8]> + AsRef<[u8]>> BufferSource for T {
+    fn size(&self) -> usize {
+        self.as_ref().len()
+    }
+
+    fn initialized(&self) -> &[u8] {
+        self.as_ref()
+    }
+
+    fn initialized_mut(&mut self) -> &mut [u8] {
+        self.as_mut()
+    }
+}
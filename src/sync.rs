@@ -0,0 +1,108 @@
+use core::cell::RefCell;
+
+use critical_section::Mutex;
+
+use crate::{Buffer, BufferIndex, BufferSource, UninitArray};
+
+/// A [`Buffer`] that can be shared between interrupts and the main loop. 
+/// Access to the buffer is guarded by a [`critical_section`].
+/// 
+/// # Example
+/// 
+/// ```rust
+///     use embytes_buffer::{CriticalSectionBuffer, StaticBuffer};
+/// 
+///     static RX: StaticBuffer<512> = CriticalSectionBuffer::new_stack();
+/// 
+///     // In an interrupt handler
+///     RX.lock(|buffer| buffer.push(&[1, 2, 3])).unwrap();
+/// 
+///     // In the main loop
+///     let len = RX.lock(|buffer| buffer.remaining_len());
+///     assert_eq!(len, 3);
+/// ```
+pub struct CriticalSectionBuffer<T: BufferSource, I: BufferIndex = usize> {
+    inner: Mutex<RefCell<Buffer<T, I>>>,
+}
+
+/// A [`CriticalSectionBuffer`] that is backed by an owned [`u8`] array with size `N`
+pub type StaticBuffer<const N: usize> = CriticalSectionBuffer<[u8; N]>;
+
+impl <const N: usize, I: BufferIndex> CriticalSectionBuffer<[u8; N], I> {
+
+    /// Creates a new [`CriticalSectionBuffer`] that is backed by an owned [`u8`] array with size `N`
+    pub const fn new_stack() -> Self {
+        Self::new(Buffer::new_stack())
+    }
+}
+
+impl <const N: usize> CriticalSectionBuffer<UninitArray<N>> {
+
+    /// Creates a new [`CriticalSectionBuffer`] that is backed by an uninitialized [`u8`] array with size `N`, 
+    /// see [`Buffer::new_uninit`]
+    pub const fn new_uninit() -> Self {
+        Self::new(Buffer::new_uninit())
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> CriticalSectionBuffer<T, I> {
+
+    /// Creates a new [`CriticalSectionBuffer`] from a [`Buffer`]
+    pub const fn new(buffer: Buffer<T, I>) -> Self {
+        Self {
+            inner: Mutex::new(RefCell::new(buffer)),
+        }
+    }
+
+    /// Runs `f` with exclusive access to the buffer inside a critical section
+    /// 
+    /// # Panics
+    /// 
+    /// If `lock` is called again from within `f`
+    pub fn lock<R>(&self, f: impl FnOnce(&mut Buffer<T, I>) -> R) -> R {
+        critical_section::with(|cs| {
+            let mut buffer = self.inner.borrow_ref_mut(cs);
+            f(&mut buffer)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{BufferWriter, UninitArray};
+
+    use super::{CriticalSectionBuffer, StaticBuffer};
+
+    static BUFFER: StaticBuffer<8> = CriticalSectionBuffer::new_stack();
+    static SMALL: CriticalSectionBuffer<[u8; 8], u8> = CriticalSectionBuffer::new_stack();
+    static UNINIT: CriticalSectionBuffer<UninitArray<4096>> = CriticalSectionBuffer::new_uninit();
+
+    #[test]
+    fn test_static_buffer() {
+        BUFFER.lock(|buffer| {
+            let mut writer = buffer.create_writer();
+            writer[0] = 1;
+            writer[1] = 2;
+            writer.commit(2).unwrap();
+        });
+
+        let data = BUFFER.lock(|buffer| {
+            let mut tgt = [0u8; 2];
+            tgt.copy_from_slice(buffer.data());
+            buffer.skip(2).unwrap();
+            tgt
+        });
+
+        assert_eq!(data, [1, 2]);
+    }
+
+    #[test]
+    fn test_small_and_uninit_buffer() {
+        SMALL.lock(|buffer| buffer.push(&[1, 2])).unwrap();
+        assert_eq!(SMALL.lock(|buffer| buffer.remaining_len()), 2);
+
+        UNINIT.lock(|buffer| buffer.push(&[3, 4, 5])).unwrap();
+        assert_eq!(UNINIT.lock(|buffer| buffer.capacity()), 4096);
+        assert_eq!(UNINIT.lock(|buffer| buffer.data().to_vec()), [3, 4, 5]);
+    }
+}
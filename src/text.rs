@@ -0,0 +1,149 @@
+use core::str::from_utf8;
+
+use crate::{BufferError, BufferReader};
+
+/// Reads UTF-8 text from a [`BufferReader`]. 
+/// A code point that is split across two writes is kept in the buffer until it is complete.
+/// 
+/// ```rust
+///     use embytes_buffer::{Buffer, ReadWrite, TextReader};
+/// 
+///     let mut buf = Buffer::<[u8; 16]>::new_stack();
+///     buf.push(&"a👌".as_bytes()[..3]).unwrap();
+/// 
+///     let reader = buf.create_reader();
+///     assert_eq!(reader.read_str(), Ok("a"));
+///     drop(reader);
+/// 
+///     buf.push(&"a👌".as_bytes()[3..]).unwrap();
+///     let reader = buf.create_reader();
+///     assert_eq!(reader.read_str(), Ok("👌"));
+/// ```
+pub trait TextReader: BufferReader {
+
+    /// Reads the longest valid UTF-8 prefix of the unread bytes. 
+    /// A trailing partial code point is not consumed.
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoData`] if there is no complete code point
+    /// [`BufferError::InvalidUtf8`] if the unread bytes start with an invalid sequence, the reader is left untouched
+    fn read_str(&self) -> Result<&str, BufferError> {
+        let chunk = next_chunk(self.unread());
+        if chunk.valid.is_empty() {
+            if chunk.invalid.is_empty() {
+                Err(BufferError::NoData)
+            } else {
+                Err(BufferError::InvalidUtf8)
+            }
+        } else {
+            self.add_bytes_read(chunk.valid.len());
+            Ok(chunk.valid)
+        }
+    }
+
+    /// Reads the longest valid UTF-8 prefix of the unread bytes and the invalid sequence following it. 
+    /// Both are consumed, so the caller can replace the invalid bytes with [`char::REPLACEMENT_CHARACTER`]. 
+    /// A trailing partial code point is not consumed.
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoData`] if there is no complete code point and no invalid sequence
+    fn read_str_lossy(&self) -> Result<TextChunk<'_>, BufferError> {
+        let chunk = next_chunk(self.unread());
+        if chunk.valid.is_empty() && chunk.invalid.is_empty() {
+            return Err(BufferError::NoData);
+        }
+
+        self.add_bytes_read(chunk.valid.len() + chunk.invalid.len());
+        Ok(chunk)
+    }
+}
+
+impl <R: BufferReader> TextReader for R {}
+
+/// A chunk of text read by [`TextReader::read_str_lossy`]
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub struct TextChunk<'a> {
+    valid: &'a str,
+    invalid: &'a [u8],
+}
+
+impl <'a> TextChunk<'a> {
+
+    /// Returns the valid text of the chunk
+    pub fn valid(&self) -> &'a str {
+        self.valid
+    }
+
+    /// Returns the invalid sequence following the valid text. 
+    /// Is empty if the chunk ends with valid text
+    pub fn invalid(&self) -> &'a [u8] {
+        self.invalid
+    }
+}
+
+/// Splits `src` into the longest valid prefix and the invalid sequence following it. 
+/// A trailing partial code point is neither valid nor invalid.
+fn next_chunk(src: &[u8]) -> TextChunk<'_> {
+    match from_utf8(src) {
+        Ok(valid) => TextChunk { valid, invalid: &[] },
+        Err(e) => {
+            let (valid, rest) = src.split_at(e.valid_up_to());
+            let valid = from_utf8(valid).expect("valid_up_to is the length of the valid prefix");
+            let invalid = match e.error_len() {
+                Some(n) => &rest[..n],
+                None => &[],
+            };
+            TextChunk { valid, invalid }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Buffer, BufferError, BufferReader, ReadWrite};
+    use super::TextReader;
+
+    #[test]
+    fn test_read_str_split_code_point() {
+        let text = "ab👌".as_bytes();
+        let mut buf = Buffer::<[u8; 16]>::new_stack();
+        buf.push(&text[..4]).unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(reader.read_str(), Ok("ab"));
+        assert_eq!(reader.read_str(), Err(BufferError::NoData));
+        drop(reader);
+        assert_eq!(buf.data(), &text[2..4]);
+
+        buf.push(&text[4..]).unwrap();
+        let reader = buf.create_reader();
+        assert_eq!(reader.read_str(), Ok("👌"));
+        drop(reader);
+        assert!(buf.data().is_empty());
+    }
+
+    #[test]
+    fn test_read_str_invalid() {
+        let mut buf = Buffer::<[u8; 16]>::new_stack();
+        buf.push(b"ab\xffcd\xf0\x9f").unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(reader.read_str(), Ok("ab"));
+        assert_eq!(reader.read_str(), Err(BufferError::InvalidUtf8));
+        assert_eq!(reader.unread(), b"\xffcd\xf0\x9f");
+        drop(reader);
+
+        let reader = buf.create_reader();
+        let chunk = reader.read_str_lossy().unwrap();
+        assert_eq!((chunk.valid(), chunk.invalid()), ("", &b"\xff"[..]));
+        let chunk = reader.read_str_lossy().unwrap();
+        assert_eq!((chunk.valid(), chunk.invalid()), ("cd", &b""[..]));
+        assert_eq!(reader.read_str_lossy(), Err(BufferError::NoData));
+        drop(reader);
+
+        // The partial code point is kept
+        assert_eq!(buf.data(), b"\xf0\x9f");
+    }
+}
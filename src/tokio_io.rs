@@ -0,0 +1,90 @@
+use std::{io, pin::Pin, task::{Context, Poll}};
+
+use tokio::io::{AsyncBufRead, AsyncRead, AsyncWrite, ReadBuf};
+
+use crate::{AsyncBuffer, BufferIndex, BufferSource};
+
+impl <T: BufferSource, I: BufferIndex> AsyncRead for AsyncBuffer<T, I> {
+    fn poll_read(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut ReadBuf<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        let n = match this.poll_read_into(cx, buf.initialize_unfilled()) {
+            Poll::Ready(Ok(n)) => n,
+            Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+            Poll::Pending => return Poll::Pending,
+        };
+        buf.advance(n);
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> AsyncWrite for AsyncBuffer<T, I> {
+    fn poll_write(self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        self.get_mut().poll_write_from(cx, buf)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        self.close();
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl <T: BufferSource, I: BufferIndex> AsyncBufRead for AsyncBuffer<T, I> {
+    fn poll_fill_buf(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<&[u8]>> {
+        self.get_mut().poll_fill_local(cx)
+    }
+
+    fn consume(self: Pin<&mut Self>, amt: usize) {
+        self.get_mut().consume_local(amt);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt};
+
+    use crate::{AsyncBuffer, Buffer};
+
+    #[tokio::test]
+    async fn test_tokio_read_write() {
+        let mut writer = AsyncBuffer::new(Buffer::new_heap(8));
+        let mut reader = writer.reader();
+
+        let data: Vec<u8> = (0..200).collect();
+        let expected = data.clone();
+
+        let producer = tokio::spawn(async move {
+            writer.write_all(&data).await.unwrap();
+            writer.shutdown().await.unwrap();
+        });
+
+        let mut received = Vec::new();
+        reader.read_to_end(&mut received).await.unwrap();
+        producer.await.unwrap();
+
+        assert_eq!(received, expected);
+    }
+
+    #[tokio::test]
+    async fn test_tokio_buf_read() {
+        let mut writer = AsyncBuffer::new(Buffer::new_heap(8));
+        let reader = writer.reader();
+
+        let producer = tokio::spawn(async move {
+            writer.write_all(b"one\ntwo\nthree\n").await.unwrap();
+            writer.shutdown().await.unwrap();
+        });
+
+        let mut lines = reader.lines();
+        let mut received = Vec::new();
+        while let Some(line) = lines.next_line().await.unwrap() {
+            received.push(line);
+        }
+        producer.await.unwrap();
+
+        assert_eq!(received, vec!["one", "two", "three"]);
+    }
+}
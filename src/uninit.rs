@@ -0,0 +1,300 @@
+use core::{fmt::Debug, mem::MaybeUninit, ptr::addr_of_mut};
+
+use crate::{Buffer, BufferIndex, BufferSource, CompactionPolicy};
+
+#[cfg(feature = "std")]
+use crate::BufferError;
+
+/// A byte source backed by an uninitialized [`u8`] array with size `N`.
+///
+/// The capacity of a [`Buffer`] backed by an [`UninitArray`] is `N` from the start.
+/// Bytes are initialized as they are written, so the array is never zeroed up front.
+/// Only creating a [`crate::Write`] initializes the free bytes once, because it hands them out as a slice.
+pub struct UninitArray<const N: usize> {
+    data: [MaybeUninit<u8>; N],
+    initialized: usize,
+}
+
+impl <const N: usize> UninitArray<N> {
+
+    /// Creates a new array without initializing the bytes
+    pub const fn new() -> Self {
+        Self {
+            data: [MaybeUninit::uninit(); N],
+            initialized: 0,
+        }
+    }
+}
+
+impl <const N: usize> Default for UninitArray<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl <const N: usize> Debug for UninitArray<N> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_struct("UninitArray")
+            .field("size", &N)
+            .field("initialized", &self.initialized)
+            .finish()
+    }
+}
+
+impl <const N: usize> BufferSource for UninitArray<N> {
+    fn size(&self) -> usize {
+        N
+    }
+
+    fn initialized(&self) -> &[u8] {
+        // SAFETY: the bytes up to `initialized` have been written by `write_at` or `initialize`
+        unsafe { core::slice::from_raw_parts(self.data.as_ptr() as *const u8, self.initialized) }
+    }
+
+    fn initialized_mut(&mut self) -> &mut [u8] {
+        // SAFETY: the bytes up to `initialized` have been written by `write_at` or `initialize`
+        unsafe { core::slice::from_raw_parts_mut(self.data.as_mut_ptr() as *mut u8, self.initialized) }
+    }
+
+    fn write_at(&mut self, offset: usize, data: &[u8]) {
+        let end = offset + data.len();
+        for byte in self.data.get_mut(self.initialized..offset).unwrap_or_default() {
+            byte.write(0);
+        }
+        for (byte, b) in self.data[offset..end].iter_mut().zip(data) {
+            byte.write(*b);
+        }
+        self.initialized = self.initialized.max(end);
+    }
+
+    fn initialize(&mut self) -> &mut [u8] {
+        for byte in &mut self.data[self.initialized..] {
+            byte.write(0);
+        }
+        self.initialized = N;
+        self.initialized_mut()
+    }
+}
+
+impl <const N: usize, I: BufferIndex> Buffer<UninitArray<N>, I> {
+
+    /// Returns the size of the uninitialized array
+    pub const fn capacity(&self) -> usize {
+        N
+    }
+}
+
+impl <const N: usize> Buffer<UninitArray<N>> {
+
+    /// Creates a new [`Buffer`] that is backed by an uninitialized [`u8`] array with size `N`.
+    ///
+    /// As this is a `const fn` it can be used to initialize a `static` directly.
+    pub const fn new_uninit() -> Self {
+        Self {
+            source: UninitArray::new(),
+            read_position: 0,
+            write_position: 0,
+            compaction: CompactionPolicy::Always,
+        }
+    }
+
+    /// Initializes a [`Buffer`] in place without creating a temporary on the stack.
+    /// Only the positions are written, the backing array stays uninitialized.
+    ///
+    /// This can be used with `static_cell::StaticCell::uninit` or a `static` [`MaybeUninit`].
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    ///     use core::mem::MaybeUninit;
+    ///     use embytes_buffer::{Buffer, UninitArray};
+    ///
+    ///     let mut slot = MaybeUninit::<Buffer<UninitArray<65536>>>::uninit();
+    ///     let buffer = Buffer::init_in_place(&mut slot);
+    ///     assert_eq!(buffer.capacity(), 65536);
+    ///     buffer.push(&[1, 2, 3]).unwrap();
+    /// ```
+    pub fn init_in_place(slot: &mut MaybeUninit<Self>) -> &mut Self {
+        let ptr = slot.as_mut_ptr();
+
+        // SAFETY: all fields except the `MaybeUninit` array are written before the reference is created
+        unsafe {
+            addr_of_mut!((*ptr).source.initialized).write(0);
+            addr_of_mut!((*ptr).read_position).write(0);
+            addr_of_mut!((*ptr).write_position).write(0);
+            addr_of_mut!((*ptr).compaction).write(CompactionPolicy::Always);
+            &mut *ptr
+        }
+    }
+}
+
+/// A byte source backed by a [`Vec<u8>`] that only initializes the bytes that are written,
+/// see [`UninitArray`]
+#[cfg(feature = "std")]
+#[derive(Debug, Default, Clone)]
+pub struct UninitVec {
+    data: Vec<u8>,
+    size: usize,
+}
+
+#[cfg(feature = "std")]
+impl BufferSource for UninitVec {
+    fn size(&self) -> usize {
+        self.size
+    }
+
+    fn initialized(&self) -> &[u8] {
+        &self.data
+    }
+
+    fn initialized_mut(&mut self) -> &mut [u8] {
+        &mut self.data
+    }
+
+    fn write_at(&mut self, offset: usize, data: &[u8]) {
+        assert!(offset + data.len() <= self.size, "write exceeds the size of the source");
+        if offset > self.data.len() {
+            self.data.resize(offset, 0);
+        }
+
+        let overlap = (self.data.len() - offset).min(data.len());
+        self.data[offset..offset + overlap].copy_from_slice(&data[..overlap]);
+        self.data.extend_from_slice(&data[overlap..]);
+    }
+
+    fn initialize(&mut self) -> &mut [u8] {
+        self.data.resize(self.size, 0);
+        &mut self.data
+    }
+}
+
+#[cfg(feature = "std")]
+impl Buffer<UninitVec> {
+
+    /// Creates a new [`Buffer`] with a capacity of `size` bytes that are allocated but not initialized
+    pub fn new_heap_uninit(size: usize) -> Self {
+        Self {
+            source: UninitVec { data: Vec::with_capacity(size), size },
+            read_position: 0,
+            write_position: 0,
+            compaction: CompactionPolicy::Always,
+        }
+    }
+
+    /// Returns the size of the source
+    pub fn capacity(&self) -> usize {
+        self.source.size
+    }
+
+    /// Grows the buffer capacity by `grow_by` bytes without initializing them
+    pub fn grow(&mut self, grow_by: usize) {
+        self.source.size += grow_by;
+        self.source.data.reserve_exact(self.source.size - self.source.data.len());
+    }
+
+    /// Shrink the buffer capacity by `shrink_by` bytes.
+    /// If this would remove written data, an [`BufferError::NoCapacity`] is returned.
+    pub fn shrink(&mut self, shrink_by: usize) -> Result<(), BufferError> {
+        if self.remaining_capacity() < shrink_by {
+            Err(BufferError::NoCapacity)
+        } else {
+            self.source.size -= shrink_by;
+            self.source.data.truncate(self.source.size);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::mem::MaybeUninit;
+
+    use crate::{Buffer, BufferError, BufferSource, BufferWriter, UninitArray};
+
+    #[test]
+    fn test_new_uninit_has_full_capacity() {
+        let mut buf = Buffer::<UninitArray<8>>::new_uninit();
+        assert_eq!(buf.capacity(), 8);
+        assert_eq!(buf.source.initialized().len(), 0);
+
+        buf.push(&[1, 2, 3, 4]).unwrap();
+        buf.push_all(&[&[5], &[6]]).unwrap();
+        assert_eq!(buf.data(), &[1, 2, 3, 4, 5, 6]);
+
+        // Only the written bytes are initialized
+        assert_eq!(buf.source.initialized().len(), 6);
+        assert_eq!(buf.push(&[7, 8, 9]), Err(BufferError::NoCapacity));
+    }
+
+    #[test]
+    fn test_shift_and_write_again() {
+        let mut buf = Buffer::<UninitArray<4>>::new_uninit();
+        buf.push(&[1, 2, 3]).unwrap();
+        buf.skip(2).unwrap();
+        buf.push(&[4, 5, 6]).unwrap();
+        assert_eq!(buf.data(), &[3, 4, 5, 6]);
+        assert_eq!(buf.source.initialized().len(), 4);
+    }
+
+    #[test]
+    fn test_writer_initializes_free_bytes() {
+        let mut buf = Buffer::<UninitArray<8>>::new_uninit();
+        buf.push(&[1]).unwrap();
+
+        let mut writer = buf.create_writer();
+        assert_eq!(writer.remaining_capacity(), 7);
+        writer[0] = 2;
+        writer.commit(1).unwrap();
+        drop(writer);
+
+        assert_eq!(buf.data(), &[1, 2]);
+        assert_eq!(buf.source.initialized().len(), 8);
+    }
+
+    #[test]
+    fn test_slot_without_deref() {
+        let mut buf = Buffer::<UninitArray<8>>::new_uninit();
+
+        let mut writer = buf.create_writer();
+        let _first = writer.reserve_slot(2).unwrap();
+        let mut second = writer.reserve_slot(1).unwrap();
+        writer.fill_slot(&mut second, &[9]).unwrap();
+        drop(writer);
+
+        // The gap before the second slot was initialized
+        assert_eq!(buf.source.initialized(), &[0, 0, 9]);
+        assert!(buf.data().is_empty());
+    }
+
+    #[test]
+    fn test_init_in_place() {
+        static mut SLOT: MaybeUninit<Buffer<UninitArray<1024>>> = MaybeUninit::uninit();
+
+        #[allow(static_mut_refs)]
+        let buf = Buffer::init_in_place(unsafe { &mut SLOT });
+        assert_eq!(buf.capacity(), 1024);
+        buf.push(&[7, 8]).unwrap();
+        assert_eq!(buf.data(), &[7, 8]);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_uninit_vec() {
+        let mut buf = Buffer::new_heap_uninit(4);
+        assert_eq!(buf.capacity(), 4);
+        buf.push(&[1, 2, 3]).unwrap();
+        assert_eq!(buf.source.initialized().len(), 3);
+
+        buf.grow(2);
+        buf.push(&[4, 5, 6]).unwrap();
+        assert_eq!(buf.data(), &[1, 2, 3, 4, 5, 6]);
+        assert_eq!(buf.push(&[7]), Err(BufferError::NoCapacity));
+
+        buf.skip(4).unwrap();
+        buf.shift();
+        buf.shrink(3).unwrap();
+        assert_eq!(buf.capacity(), 3);
+        assert_eq!(buf.data(), &[5, 6]);
+        assert_eq!(buf.shrink(2), Err(BufferError::NoCapacity));
+    }
+}
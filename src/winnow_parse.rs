@@ -0,0 +1,109 @@
+use winnow::{error::{ErrMode, Needed}, stream::Partial, Parser};
+
+use crate::{BufferReader, ParseError};
+
+/// Runs [`winnow`] parsers on the unread bytes of a [`BufferReader`] wrapped as [`Partial`] stream
+/// 
+/// ```rust
+///     use embytes_buffer::{Buffer, ParseError, ReadWrite, WinnowReader};
+///     use winnow::{binary::be_u16, error::ContextError, stream::Partial, ModalResult, Parser};
+/// 
+///     fn word(input: &mut Partial<&[u8]>) -> ModalResult<u16> {
+///         be_u16.parse_next(input)
+///     }
+/// 
+///     let mut buf = Buffer::<[u8; 8]>::new_stack();
+///     buf.push(&[0x01]).unwrap();
+/// 
+///     let reader = buf.create_reader();
+///     let result = reader.parse_winnow::<_, ContextError, _>(word);
+///     assert!(matches!(result, Err(ParseError::Incomplete(Some(n))) if n.get() == 1));
+///     drop(reader);
+/// 
+///     buf.push(&[0x02]).unwrap();
+///     let reader = buf.create_reader();
+///     assert_eq!(reader.parse_winnow::<_, ContextError, _>(word), Ok(0x0102));
+/// ```
+pub trait WinnowReader: BufferReader {
+
+    /// Runs `parser` on the unread bytes and marks the bytes used by the parser as read
+    /// 
+    /// # Errors
+    /// 
+    /// [`ParseError::Incomplete`] if the parser returned [`ErrMode::Incomplete`], nothing is consumed
+    /// [`ParseError::Parse`] if the parser returned [`ErrMode::Backtrack`] or [`ErrMode::Cut`], nothing is consumed
+    fn parse_winnow<'a, O, E, P>(&'a self, mut parser: P) -> Result<O, ParseError<E>>
+        where P: Parser<Partial<&'a [u8]>, O, ErrMode<E>>
+    {
+        let src = self.unread();
+        let mut input = Partial::new(src);
+        match parser.parse_next(&mut input) {
+            Ok(output) => {
+                self.add_bytes_read(src.len() - input.into_inner().len());
+                Ok(output)
+            },
+            Err(ErrMode::Incomplete(Needed::Size(n))) => Err(ParseError::Incomplete(Some(n))),
+            Err(ErrMode::Incomplete(Needed::Unknown)) => Err(ParseError::Incomplete(None)),
+            Err(ErrMode::Backtrack(e) | ErrMode::Cut(e)) => Err(ParseError::Parse(e)),
+        }
+    }
+}
+
+impl <R: BufferReader> WinnowReader for R {}
+
+#[cfg(test)]
+mod tests {
+    use winnow::{combinator::{alt, cut_err, preceded, terminated}, error::ContextError, stream::{AsChar, Partial}, token::{literal, take, take_until, take_while}, ModalResult, Parser};
+
+    use crate::{Buffer, BufferReader, ParseError, ReadWrite};
+    use super::WinnowReader;
+
+    /// Parses a line terminated by `\r\n`
+    fn line<'a>(input: &mut Partial<&'a [u8]>) -> ModalResult<&'a [u8]> {
+        terminated(take_until(0.., &b"\r\n"[..]), literal(b"\r\n")).parse_next(input)
+    }
+
+    /// Parses `$<digits>` or falls back to two raw bytes. 
+    /// After a `$` the digits are required: the alternative is not tried.
+    fn command<'a>(input: &mut Partial<&'a [u8]>) -> ModalResult<&'a [u8]> {
+        alt((
+            preceded(literal(b"$"), cut_err(take_while(1.., AsChar::is_dec_digit))),
+            take(2usize),
+        )).parse_next(input)
+    }
+
+    #[test]
+    fn test_take_until_needed_unknown() {
+        let mut buf = Buffer::<[u8; 16]>::new_stack();
+        buf.push(b"OK\r").unwrap();
+
+        let reader = buf.create_reader();
+        assert_eq!(reader.parse_winnow(line), Err(ParseError::Incomplete(None)));
+        assert_eq!(reader.bytes_read(), 0);
+        drop(reader);
+
+        buf.push(b"\nERROR").unwrap();
+        let reader = buf.create_reader();
+        assert_eq!(reader.parse_winnow(line), Ok(&b"OK"[..]));
+        assert_eq!(reader.unread(), b"ERROR");
+    }
+
+    #[test]
+    fn test_cut_maps_to_parse_error() {
+        let mut buf = Buffer::<[u8; 16]>::new_stack();
+        buf.push(b"$x1").unwrap();
+
+        let reader = buf.create_reader();
+        let result: Result<_, ParseError<ContextError>> = reader.parse_winnow(command);
+        assert!(matches!(result, Err(ParseError::Parse(_))));
+        assert_eq!(reader.bytes_read(), 0);
+        drop(reader);
+
+        buf.reset();
+        buf.push(b"ab$12;").unwrap();
+        let reader = buf.create_reader();
+        assert_eq!(reader.parse_winnow(command), Ok(&b"ab"[..]));
+        assert_eq!(reader.parse_winnow(command), Ok(&b"12"[..]));
+        assert_eq!(reader.unread(), b";");
+    }
+}
@@ -1,6 +1,6 @@
-use core::{cell::Cell, ops::{Deref, DerefMut}};
+use core::{cell::Cell, marker::PhantomData, ops::{Deref, DerefMut}};
 
-use crate::{Buffer, BufferError};
+use crate::{Buffer, BufferCapacity, BufferError, BufferIndex, BufferSource};
 
 /// A Writer to write to a [`Buffer`] as it is a writeable slice
 pub trait BufferWriter: DerefMut<Target = [u8]> {
@@ -18,25 +18,150 @@ pub trait BufferWriter: DerefMut<Target = [u8]> {
     }
 }
 
+/// A handle to a slot reserved with [`Write::reserve_slot`]
+/// 
+/// The handle borrows the buffer like the writer that reserved it, 
+/// so it can not be used after the writer is dropped:
+/// 
+/// ```compile_fail
+///     use embytes_buffer::Buffer;
+/// 
+///     let mut buffer = Buffer::<[u8; 8]>::new_stack();
+///     let writer = buffer.create_writer();
+///     let mut slot = writer.reserve_slot(1).unwrap();
+///     drop(writer);
+/// 
+///     let mut writer = buffer.create_writer();
+///     writer.fill_slot(&mut slot, &[1]).unwrap();
+/// ```
+#[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SlotHandle<'a> {
+    buffer: usize,
+    offset: usize,
+    len: usize,
+    filled: bool,
+    _buffer: PhantomData<&'a ()>,
+}
+
+impl <'a> SlotHandle<'a> {
+    /// Returns the size of the slot in bytes
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the slot has a size of `0`
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the slot was filled with [`Write::fill_slot`]
+    pub fn is_filled(&self) -> bool {
+        self.filled
+    }
+}
+
 /// An implementation of [`BufferWriter`] for [`Buffer`]
-pub struct Write<'a, T: AsMut<[u8]> + AsRef<[u8]>> {
-    buffer: &'a mut Buffer<T>,
-    bytes_written: Cell<usize>
+pub struct Write<'a, T: BufferSource, I: BufferIndex = usize> {
+    buffer: &'a mut Buffer<T, I>,
+    bytes_written: Cell<usize>,
+    unfilled_slots: Cell<usize>,
+    first_slot_offset: Cell<Option<usize>>,
 }
 
-impl <'a, T: AsMut<[u8]> + AsRef<[u8]>> Write<'a, T> {
-    pub(crate) fn new(buffer: &'a mut Buffer<T>) -> Self {
+impl <'a, T: BufferSource, I: BufferIndex> Write<'a, T, I> {
+    pub(crate) fn new(buffer: &'a mut Buffer<T, I>) -> Self {
         Self {
             buffer,
-            bytes_written: Cell::new(0)
+            bytes_written: Cell::new(0),
+            unfilled_slots: Cell::new(0),
+            first_slot_offset: Cell::new(None),
+        }
+    }
+
+    /// Returns the address of the buffer to tell the slots of writers to different buffers apart
+    fn buffer_address(&self) -> usize {
+        &*self.buffer as *const Buffer<T, I> as usize
+    }
+
+    /// Reserves a slot of `n` bytes at the current position that is filled later with [`Write::fill_slot`].
+    /// This allows to write a header (e.g. a length) after the payload has been written.
+    /// 
+    /// [`BufferWriter::commit`] returns [`BufferError::InvalidSlot`] until all slots are filled, 
+    /// so a message with an unfilled slot never becomes readable. 
+    /// If the writer is dropped with unfilled slots, everything from the first reserved slot on is discarded.
+    /// 
+    /// # Example
+    /// 
+    /// ```rust
+    ///     use embytes_buffer::{Buffer, BufferWriter};
+    /// 
+    ///     let mut buffer = Buffer::<[u8; 8]>::new_stack();
+    ///     let mut writer = buffer.create_writer();
+    ///     let mut len = writer.reserve_slot(1).unwrap();
+    ///     writer[..3].copy_from_slice(b"abc");
+    ///     writer.fill_slot(&mut len, &[3]).unwrap();
+    ///     writer.commit(3).unwrap();
+    ///     drop(writer);
+    /// 
+    ///     assert_eq!(buffer.data(), b"\x03abc");
+    /// ```
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::NoCapacity`] if `n > self.remaining_capacity()`
+    pub fn reserve_slot(&self, n: usize) -> Result<SlotHandle<'a>, BufferError> {
+        if self.remaining_capacity() < n {
+            return Err(BufferError::NoCapacity);
         }
+
+        let offset = self.bytes_written.get();
+        self.bytes_written.set(offset + n);
+        self.unfilled_slots.set(self.unfilled_slots.get() + 1);
+        if self.first_slot_offset.get().is_none() {
+            self.first_slot_offset.set(Some(offset));
+        }
+
+        Ok(SlotHandle { buffer: self.buffer_address(), offset, len: n, filled: false, _buffer: PhantomData })
+    }
+
+    /// Fills a slot reserved with [`Write::reserve_slot`] with `data`.
+    /// The handle is kept on error, so the slot can be filled again. Filling a slot twice overwrites the first data.
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::InvalidSlot`] if `data.len()` does not match the size of the slot 
+    /// or the slot was not reserved by this writer
+    pub fn fill_slot(&mut self, slot: &mut SlotHandle<'a>, data: &[u8]) -> Result<(), BufferError> {
+        if slot.buffer != self.buffer_address() 
+            || slot.offset + slot.len > self.bytes_written.get() 
+            || data.len() != slot.len 
+        {
+            return Err(BufferError::InvalidSlot);
+        }
+
+        let start = self.buffer.wpos() + slot.offset;
+        self.buffer.source.write_at(start, data);
+        if !slot.filled {
+            slot.filled = true;
+            self.unfilled_slots.set(self.unfilled_slots.get() - 1);
+        }
+        Ok(())
     }
 }
 
-impl <'a, T: AsMut<[u8]> + AsRef<[u8]>> BufferWriter for Write<'a, T> {
+impl <'a, T: BufferSource, I: BufferIndex> BufferWriter for Write<'a, T, I> {
 
+    /// Commits `n` bytes
+    /// 
+    /// # Errors
+    /// 
+    /// [`BufferError::InvalidSlot`] if a slot reserved with [`Write::reserve_slot`] is not filled
+    /// [`BufferError::NoCapacity`] if `n > self.remaining_capacity()`
     fn commit(&self, n: usize) -> Result<(), BufferError> {
-        if self.remaining_capacity() < n {
+        if self.unfilled_slots.get() > 0 {
+            Err(BufferError::InvalidSlot)
+        } else if self.remaining_capacity() < n {
             Err(BufferError::NoCapacity)
         } else {
             self.bytes_written.set(
@@ -47,42 +172,52 @@ impl <'a, T: AsMut<[u8]> + AsRef<[u8]>> BufferWriter for Write<'a, T> {
     }
 
     fn remaining_capacity(&self) -> usize {
-        self.buffer.capacity() - self.buffer.write_position - self.bytes_written.get()
+        self.buffer.capacity() - self.buffer.wpos() - self.bytes_written.get()
     }
 }
 
-impl <'a, T: AsMut<[u8]> + AsRef<[u8]>> Drop for Write<'a, T> {
+impl <'a, T: BufferSource, I: BufferIndex> Drop for Write<'a, T, I> {
     fn drop(&mut self) {
         
-        self.buffer.write_position += self.bytes_written.get();
-        if self.buffer.write_position > self.buffer.source.as_ref().len() {
+        let bytes_written = match self.first_slot_offset.get() {
+            Some(offset) if self.unfilled_slots.get() > 0 => offset,
+            _ => self.bytes_written.get(),
+        };
+        let write_position = self.buffer.wpos() + bytes_written;
+        if write_position > self.buffer.capacity() {
             panic!("illegal state: Write<'a, T> committed more bytes than available!")
         }
+        if write_position > self.buffer.source.initialized().len() {
+            // Bytes were committed without writing to them
+            self.buffer.source.initialize();
+        }
+        self.buffer.set_wpos(write_position);
 
     }
 }
 
-impl <'a, T: AsMut<[u8]> + AsRef<[u8]>> Deref for Write<'a, T>{
+impl <'a, T: BufferSource, I: BufferIndex> Deref for Write<'a, T, I>{
     type Target = [u8];
 
     fn deref(&self) -> &Self::Target {
-        let tgt = self.buffer.source.as_ref();
-        let offset = self.buffer.write_position + self.bytes_written.get();
-        &tgt[offset..]
+        // Only the initialized bytes can be borrowed immutable, see `deref_mut`
+        let tgt = self.buffer.source.initialized();
+        let offset = self.buffer.wpos() + self.bytes_written.get();
+        tgt.get(offset..).unwrap_or_default()
     }
 }
 
-impl <'a, T: AsMut<[u8]> + AsRef<[u8]>> DerefMut for Write<'a, T>{
+impl <'a, T: BufferSource, I: BufferIndex> DerefMut for Write<'a, T, I>{
     fn deref_mut(&mut self) -> &mut Self::Target {
-        let tgt = self.buffer.source.as_mut();
-        let offset = self.buffer.write_position + self.bytes_written.get();
+        let offset = self.buffer.wpos() + self.bytes_written.get();
+        let tgt = self.buffer.source.initialize();
         &mut tgt[offset..]
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{Buffer, BufferWriter, ReadWrite};
+    use crate::{Buffer, BufferError, BufferWriter};
 
 
     #[test]
@@ -124,4 +259,104 @@ mod tests {
         assert_eq!(buf.write_position, 4);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn test_slot_filled_after_payload() {
+        let mut b = [0u8; 8];
+        let mut buf = Buffer::new(&mut b);
+
+        let mut write = buf.create_writer();
+        let mut slot = write.reserve_slot(1).unwrap();
+        write[0] = 7;
+        write[1] = 8;
+        write[2] = 9;
+        write.fill_slot(&mut slot, &[3]).unwrap();
+        assert!(slot.is_filled());
+        write.commit(3).unwrap();
+        drop(write);
+
+        assert_eq!(buf.data(), &[3, 7, 8, 9]);
+    }
+
+    #[test]
+    fn test_commit_refused_with_unfilled_slot() {
+        let mut b = [0u8; 8];
+        let mut buf = Buffer::new(&mut b);
+
+        let mut write = buf.create_writer();
+        write[0] = 1;
+        write.commit(1).unwrap();
+
+        let _slot = write.reserve_slot(2).unwrap();
+        write[0] = 5;
+        assert_eq!(write.commit(1), Err(BufferError::InvalidSlot));
+        drop(write);
+
+        // The reserved slot is discarded with the writer
+        assert_eq!(buf.data(), &[1]);
+    }
+
+    #[test]
+    fn test_fill_slot_size_mismatch() {
+        let mut b = [0u8; 8];
+        let mut buf = Buffer::new(&mut b);
+
+        let mut write = buf.create_writer();
+        let mut slot = write.reserve_slot(2).unwrap();
+        assert_eq!(slot.len(), 2);
+        assert_eq!(write.fill_slot(&mut slot, &[1]), Err(BufferError::InvalidSlot));
+        assert!(!slot.is_filled());
+
+        // The handle is kept and the slot can still be filled
+        write.fill_slot(&mut slot, &[1, 2]).unwrap();
+        drop(write);
+
+        assert_eq!(buf.data(), &[1, 2]);
+    }
+
+    #[test]
+    fn test_fill_slot_twice() {
+        let mut b = [0u8; 8];
+        let mut buf = Buffer::new(&mut b);
+
+        let mut write = buf.create_writer();
+        let mut first = write.reserve_slot(1).unwrap();
+        let mut second = write.reserve_slot(1).unwrap();
+        write.fill_slot(&mut first, &[1]).unwrap();
+        write.fill_slot(&mut first, &[3]).unwrap();
+
+        // Filling the first slot twice does not fill the second
+        assert_eq!(write.commit(0), Err(BufferError::InvalidSlot));
+
+        write.fill_slot(&mut second, &[2]).unwrap();
+        write.commit(0).unwrap();
+        drop(write);
+
+        assert_eq!(buf.data(), &[3, 2]);
+    }
+
+    #[test]
+    fn test_fill_slot_of_other_writer() {
+        let mut a = Buffer::<[u8; 8]>::new_stack();
+        let mut b = Buffer::<[u8; 8]>::new_stack();
+
+        let write_a = a.create_writer();
+        let mut other = write_a.reserve_slot(1).unwrap();
+
+        let mut write_b = b.create_writer();
+        let _slot = write_b.reserve_slot(1).unwrap();
+        assert_eq!(write_b.fill_slot(&mut other, &[1]), Err(BufferError::InvalidSlot));
+        drop(write_b);
+
+        assert_eq!(b.data(), &[] as &[u8]);
+    }
+
+    #[test]
+    fn test_reserve_slot_no_capacity() {
+        let mut b = [0u8; 4];
+        let mut buf = Buffer::new(&mut b);
+
+        let write = buf.create_writer();
+        assert_eq!(write.reserve_slot(5), Err(BufferError::NoCapacity));
+    }
+
+}